@@ -0,0 +1,201 @@
+use crate::fields::Fields;
+use crate::format_string::{parse_format_string, FormatPart};
+use crate::keyword_trie::expr_keywords;
+use crate::parse_format::{find_placeholder_field, ParseFormat};
+use crate::parsed_item::{Fallback, ParsedItem, ParsedVariant};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::{parse_quote, Generics, Type};
+
+/// Generates a `Display` implementation that writes the item in the layout of its `match` format.
+/// Any error is turned into a `compile_error!`.
+pub(crate) fn generate_display(
+    parse_format: &ParseFormat,
+    parsed_item: &ParsedItem,
+) -> TokenStream {
+    match display_content(parse_format, parsed_item) {
+        Ok(Some((content, types))) => {
+            let (name, generics) = match parsed_item {
+                ParsedItem::Struct { object, .. } => (&object.ident, &object.generics),
+                ParsedItem::Enum { object, .. } => (&object.ident, &object.generics),
+            };
+            generate_display_impl(name, generics, &types, content)
+        }
+        // The parser already reports the error in the format
+        Ok(None) => TokenStream::new(),
+        Err(err) => err.to_compile_error(),
+    }
+}
+
+fn display_content(
+    parse_format: &ParseFormat,
+    parsed_item: &ParsedItem,
+) -> syn::Result<Option<(TokenStream, Vec<Type>)>> {
+    match parsed_item {
+        ParsedItem::Struct { fields, .. } => {
+            let ParseFormat::Match(format) = parse_format else {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "The display option can only be used with a match format",
+                ));
+            };
+            let Some((statements, types)) = write_match_format(&format.literal, fields)? else {
+                return Ok(None);
+            };
+            let pattern = fields.create_instance_expr(None);
+
+            Ok(Some((
+                quote! {
+                    let #pattern = self;
+                    #(#statements)*
+                    Ok(())
+                },
+                types,
+            )))
+        }
+        ParsedItem::Enum { variants, .. } => {
+            if *parse_format != ParseFormat::Default {
+                return Err(syn::Error::new(
+                    Span::call_site(),
+                    "The display option can not be used with the format of an enum",
+                ));
+            }
+
+            let mut arms = Vec::with_capacity(variants.len());
+            let mut types = vec![];
+            for variant in variants {
+                let Some((statements, variant_types)) = write_variant(variant)? else {
+                    return Ok(None);
+                };
+                let pattern = variant.fields.create_instance_expr(Some(&variant.name));
+                arms.push(quote! { #pattern => { #(#statements)* } });
+                types.extend(variant_types);
+            }
+
+            Ok(Some((
+                quote! {
+                    match self {
+                        #(#arms)*
+                    }
+                    Ok(())
+                },
+                types,
+            )))
+        }
+    }
+}
+
+/// Generates the statements to write a single variant. Besides `match` formats, keywords,
+/// variants without a format or fields and the text captured by a fallback can be written as well.
+/// Skipped variants have no text to write, so they are an error.
+fn write_variant(variant: &ParsedVariant) -> syn::Result<Option<(Vec<TokenStream>, Vec<Type>)>> {
+    if variant.skip {
+        return Err(syn::Error::new_spanned(
+            &variant.name,
+            format!(
+                "Variant {} is skipped by the parser, so it can not be used with the display option",
+                variant.name
+            ),
+        ));
+    }
+    if matches!(variant.fallback, Some(Fallback::Token | Fallback::Line)) {
+        let format = syn::LitStr::new("{}", variant.name.span());
+        return write_match_format(&format, &variant.fields);
+    }
+
+    match &variant.format {
+        ParseFormat::Match(format) => write_match_format(&format.literal, &variant.fields),
+        // Keywords are written as their first literal, since the aliases parse to the same variant
+        ParseFormat::Expr(expr) if expr_keywords(expr).is_some() => {
+            let keyword = expr_keywords(expr).unwrap().remove(0);
+            let keyword = String::from_utf8_lossy(&keyword);
+            Ok(Some((vec![quote! { f.write_str(#keyword)?; }], vec![])))
+        }
+        ParseFormat::Default if variant.fields.get_expression_names().is_empty() => {
+            Ok(Some((vec![], vec![])))
+        }
+        _ => Err(syn::Error::new_spanned(
+            &variant.name,
+            format!(
+                "Variant {} needs a match format to be used with the display option",
+                variant.name
+            ),
+        )),
+    }
+}
+
+/// Generates the statements to write the fields in the layout of a `match` format, together with
+/// the types of the fields that need a `Display` implementation. Returns `None` when the format
+/// is invalid, since the parser reports those errors. Only placeholders that are parsed with the
+/// `ParseFrom` implementation of their field can be written with its `Display` implementation.
+fn write_match_format(
+    literal: &syn::LitStr,
+    fields: &Fields,
+) -> syn::Result<Option<(Vec<TokenStream>, Vec<Type>)>> {
+    let mut statements = vec![];
+    let mut types = vec![];
+    let mut next_position = 0;
+
+    let Ok(parts) = parse_format_string(literal) else {
+        return Ok(None);
+    };
+    for part in parts {
+        match part {
+            FormatPart::Literal(text) => statements.push(quote! { f.write_str(#text)?; }),
+            FormatPart::Placeholder { name, spec, span } => {
+                let Ok(field) =
+                    find_placeholder_field(name.as_deref(), fields, &mut next_position, span)
+                else {
+                    return Ok(None);
+                };
+                if spec.is_some() {
+                    return Err(syn::Error::new(
+                        span,
+                        "The display option can not be used with a placeholder format",
+                    ));
+                }
+                if field.get_format().is_some()
+                    || field.get_parse_with().is_some()
+                    || field.get_verify().is_some()
+                    || field.generate_conversion().is_some()
+                {
+                    return Err(syn::Error::new(
+                        span,
+                        "The display option can only be used with fields that are parsed with their \
+                         ParseFrom implementation",
+                    ));
+                }
+                let name = field.get_param_name();
+                statements.push(quote! { core::write!(f, "{}", #name)?; });
+                types.push(field.get_type().clone());
+            }
+        }
+    }
+
+    Ok(Some((statements, types)))
+}
+
+fn generate_display_impl(
+    name: &Ident,
+    generics: &Generics,
+    types: &[Type],
+    content: TokenStream,
+) -> TokenStream {
+    let mut generics = generics.clone();
+    let predicates = &mut generics.make_where_clause().predicates;
+    for ty in types {
+        predicates.push(parse_quote! { #ty: core::fmt::Display });
+    }
+    let (impl_generics, type_generics, where_statement) = generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics core::fmt::Display for #name #type_generics
+        #where_statement
+        {
+            #[allow(unused_variables)]
+            fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                #content
+            }
+        }
+    }
+}
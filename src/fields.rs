@@ -2,13 +2,56 @@ use itertools::Itertools;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::quote;
 use std::collections::HashMap;
+use syn::parse::{Parse, ParseStream};
+use syn::parse_quote;
 use syn::punctuated::Punctuated;
+use syn::visit::Visit;
 use syn::visit_mut::VisitMut;
-use syn::{Expr, FieldsNamed, FieldsUnnamed, Path, Result, Type};
+use syn::{
+    Expr, ExprClosure, FieldsNamed, FieldsUnnamed, Meta, PatIdent, Path, Result, Token, Type,
+    TypePath,
+};
 
+// The fields are only created once per field while expanding the macro, so their size is no concern
+#[allow(clippy::large_enum_variant)]
 pub enum FieldFormat {
-    Expression { name: Ident, ty: Type },
-    Derived { name: Ident, ty: Type, expr: Expr },
+    Expression {
+        name: Ident,
+        ty: Type,
+        format: Option<Expr>,
+        parse_with: Option<Path>,
+        /// The predicate given with `#[verify(...)]`, where the name of the field is a reference
+        /// to the parsed value.
+        verify: Option<Expr>,
+        /// The conversion from the parsed value to the type of the field.
+        conversion: Option<Conversion>,
+    },
+    Derived {
+        name: Ident,
+        ty: Type,
+        expr: Expr,
+        /// Set with `#[try_derived(...)]`, where the expression returns a `Result`.
+        fallible: bool,
+        /// The error type of a fallible expression given with `error = ...`, which is passed on
+        /// with `FromExternalError`.
+        error: Option<Type>,
+    },
+    /// A field that is not parsed, but set to the expression given with `#[default = ...]`, or
+    /// to `Default::default()`.
+    Default {
+        name: Ident,
+        ty: Type,
+        expr: Option<Expr>,
+    },
+}
+
+/// How a field is converted after it is parsed as another type.
+pub enum Conversion {
+    /// `#[from(Type)]` parses the field as the given type and converts it with `TryFrom`, which
+    /// also covers the types that implement `From`.
+    From(Type),
+    /// `#[map(path)]` converts the parsed value with the given function.
+    Map(Path),
 }
 
 pub struct Fields {
@@ -58,27 +101,193 @@ fn parse_field_iterator<'a>(
         name.set_span(Span::call_site());
         let ty = field.ty.clone();
 
-        if let Some((ix, attr)) = field
-            .attrs
-            .iter()
-            .find_position(|attr| attr.path().is_ident("derived"))
+        let format = take_attribute::<Expr>(field, "format")?;
+        let parse_with = take_attribute::<Path>(field, "parse_with")?;
+        let verify = take_attribute::<Expr>(field, "verify")?;
+        let from = take_attribute::<Type>(field, "from")?;
+        let map = take_attribute::<Path>(field, "map")?;
+        let conversion = match (from, map) {
+            (Some(_), Some(map)) => {
+                return Err(syn::Error::new_spanned(
+                    map,
+                    "A field can not have both a from type and a map function",
+                ));
+            }
+            (Some(from), None) => Some(Conversion::From(from)),
+            (None, Some(map)) => Some(Conversion::Map(map)),
+            (None, None) => None,
+        };
+
+        if let (Some(format), Some(_)) = (&format, &parse_with) {
+            return Err(syn::Error::new_spanned(
+                format,
+                "A field can not have both a format and a parse_with function",
+            ));
+        }
+
+        let derived = take_attribute::<Expr>(field, "derived")?;
+        let try_derived = take_attribute::<TryDerivedAttribute>(field, "try_derived")?;
+        let default = take_default_attribute(field)?;
+
+        if let (Some(expr), Some(_)) = (&derived, &try_derived) {
+            return Err(syn::Error::new_spanned(
+                expr,
+                "A field can not be both derived and try_derived",
+            ));
+        }
+        let derived = derived
+            .map(|expr| (expr, false, None))
+            .or(try_derived.map(|attribute| (attribute.expr, true, attribute.error)));
+
+        if derived.is_some() || default.is_some() {
+            if let Some(verify) = verify {
+                return Err(syn::Error::new_spanned(
+                    verify,
+                    "Only parsed fields can be verified",
+                ));
+            }
+            if conversion.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "Only parsed fields can be converted with from or map",
+                ));
+            }
+        }
+
+        if let Some((expr, fallible, error)) = derived {
+            if format.is_some() || parse_with.is_some() || default.is_some() {
+                return Err(syn::Error::new_spanned(
+                    expr,
+                    "A derived field can not have a format or default",
+                ));
+            }
+            result.push(FieldFormat::Derived {
+                name,
+                ty,
+                expr,
+                fallible,
+                error,
+            });
+        } else if let Some(expr) = default {
+            if format.is_some() || parse_with.is_some() {
+                return Err(syn::Error::new_spanned(
+                    &field.ty,
+                    "A default field can not have a format",
+                ));
+            }
+            result.push(FieldFormat::Default { name, ty, expr });
+        } else if format.is_none()
+            && parse_with.is_none()
+            && verify.is_none()
+            && conversion.is_none()
+            && is_phantom_data(&ty)
         {
-            let expr = attr.parse_args::<Expr>()?;
-            field.attrs.remove(ix);
-            result.push(FieldFormat::Derived { name, ty, expr });
+            result.push(FieldFormat::Default {
+                name,
+                ty,
+                expr: None,
+            });
         } else {
-            result.push(FieldFormat::Expression { name, ty });
+            result.push(FieldFormat::Expression {
+                name,
+                ty,
+                format,
+                parse_with,
+                verify,
+                conversion,
+            });
         }
     }
 
     Ok(result)
 }
 
+/// Removes the attribute with the given name from the field and returns its argument.
+fn take_attribute<T: Parse>(field: &mut syn::Field, name: &str) -> Result<Option<T>> {
+    if let Some((ix, attr)) = field
+        .attrs
+        .iter()
+        .find_position(|attr| attr.path().is_ident(name))
+    {
+        let expr = attr.parse_args::<T>()?;
+        field.attrs.remove(ix);
+        Ok(Some(expr))
+    } else {
+        Ok(None)
+    }
+}
+
+/// The arguments of `#[try_derived(expr)]` or `#[try_derived(expr, error = Type)]`.
+struct TryDerivedAttribute {
+    expr: Expr,
+    error: Option<Type>,
+}
+
+impl Parse for TryDerivedAttribute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let expr = input.parse::<Expr>()?;
+        if input.is_empty() {
+            return Ok(TryDerivedAttribute { expr, error: None });
+        }
+
+        input.parse::<Token![,]>()?;
+        let name = input.parse::<Ident>()?;
+        if name != "error" {
+            return Err(syn::Error::new_spanned(
+                &name,
+                format!("Unknown option {}, expected error = Type", name),
+            ));
+        }
+        input.parse::<Token![=]>()?;
+        let error = input.parse::<Type>()?;
+        Ok(TryDerivedAttribute {
+            expr,
+            error: Some(error),
+        })
+    }
+}
+
+/// Removes the `#[default]` or `#[default = expr]` attribute from the field. The outer `Option`
+/// tells if the attribute is present, the inner one if it has an expression.
+fn take_default_attribute(field: &mut syn::Field) -> Result<Option<Option<Expr>>> {
+    let Some((ix, attr)) = field
+        .attrs
+        .iter()
+        .find_position(|attr| attr.path().is_ident("default"))
+    else {
+        return Ok(None);
+    };
+
+    let expr = match &attr.meta {
+        Meta::Path(_) => None,
+        Meta::NameValue(name_value) => Some(name_value.value.clone()),
+        Meta::List(_) => {
+            return Err(syn::Error::new_spanned(
+                attr,
+                "Expected #[default] or #[default = expr]",
+            ))
+        }
+    };
+    field.attrs.remove(ix);
+    Ok(Some(expr))
+}
+
+/// `PhantomData` fields are never parsed, since there is nothing to parse.
+fn is_phantom_data(ty: &Type) -> bool {
+    match ty {
+        Type::Path(TypePath { path, .. }) => {
+            matches!(path.segments.last(), Some(segment) if segment.ident == "PhantomData")
+        }
+        _ => false,
+    }
+}
+
 impl FieldFormat {
     pub fn get_name(&self) -> &Ident {
         match self {
             FieldFormat::Expression { name, .. } => name,
             FieldFormat::Derived { name, .. } => name,
+            FieldFormat::Default { name, .. } => name,
         }
     }
 
@@ -86,20 +295,144 @@ impl FieldFormat {
         match self {
             FieldFormat::Expression { ty, .. } => ty,
             FieldFormat::Derived { ty, .. } => ty,
+            FieldFormat::Default { ty, .. } => ty,
         }
     }
 
+    /// The type that the field is parsed as. This differs from the type of the field when it is
+    /// converted with `#[from(...)]`, and is left to be inferred for `#[map(...)]`.
+    pub fn get_parsed_type(&self) -> Type {
+        match self {
+            FieldFormat::Expression {
+                conversion: Some(Conversion::From(ty)),
+                ..
+            } => ty.clone(),
+            FieldFormat::Expression {
+                conversion: Some(Conversion::Map(_)),
+                ..
+            } => parse_quote!(_),
+            _ => self.get_type().clone(),
+        }
+    }
+
+    /// Whether the field is parsed from the input, instead of derived or set to a default.
+    pub fn is_parsed(&self) -> bool {
+        matches!(self, FieldFormat::Expression { .. })
+    }
+
+    /// The format that is given with the `#[format(...)]` attribute on the field.
+    pub fn get_format(&self) -> Option<&Expr> {
+        match self {
+            FieldFormat::Expression { format, .. } => format.as_ref(),
+            FieldFormat::Derived { .. } | FieldFormat::Default { .. } => None,
+        }
+    }
+
+    /// The parser function that is given with the `#[parse_with(...)]` attribute on the field.
+    pub fn get_parse_with(&self) -> Option<&Path> {
+        match self {
+            FieldFormat::Expression { parse_with, .. } => parse_with.as_ref(),
+            FieldFormat::Derived { .. } | FieldFormat::Default { .. } => None,
+        }
+    }
+
+    /// The predicate that is given with the `#[verify(...)]` attribute on the field.
+    pub fn get_verify(&self) -> Option<&Expr> {
+        match self {
+            FieldFormat::Expression { verify, .. } => verify.as_ref(),
+            FieldFormat::Derived { .. } | FieldFormat::Default { .. } => None,
+        }
+    }
+
+    /// Generates the statement that converts the parsed value to the type of the field. A failed
+    /// `TryFrom` conversion gives a `MapRes` error.
+    pub fn generate_conversion(&self) -> Option<TokenStream> {
+        let FieldFormat::Expression {
+            ty,
+            conversion: Some(conversion),
+            ..
+        } = self
+        else {
+            return None;
+        };
+
+        let name = self.get_param_name();
+        Some(match conversion {
+            Conversion::From(parsed_ty) => quote! {
+                let #name: #ty = match <#ty as core::convert::TryFrom<#parsed_ty>>::try_from(#name) {
+                    Ok(value) => value,
+                    Err(_) => {
+                        return Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+                            input,
+                            nom::error::ErrorKind::MapRes,
+                        )))
+                    }
+                };
+            },
+            Conversion::Map(path) => quote! {
+                let #name: #ty = #path(#name);
+            },
+        })
+    }
+
     pub fn generate_derived_expression(&self, fields: &Fields) -> Option<TokenStream> {
+        let name = self.get_param_name();
         match self {
             FieldFormat::Expression { .. } => None,
-            FieldFormat::Derived { expr, ty, .. } => {
-                let name = self.get_param_name();
+            FieldFormat::Derived {
+                expr,
+                ty,
+                fallible,
+                error,
+                ..
+            } => {
                 let mut expr = expr.clone();
                 fields.rename_derive_expr(&mut expr);
+                if !fallible {
+                    return Some(quote! {
+                        let #name: #ty = #expr;
+                    });
+                }
+
+                // The error points at the input after the parsed fields
+                let error_arm = match error {
+                    Some(_) => quote! {
+                        Err(error) => {
+                            return Err(nom::Err::Error(
+                                nom::error::FromExternalError::from_external_error(
+                                    input,
+                                    nom::error::ErrorKind::MapRes,
+                                    error,
+                                ),
+                            ))
+                        }
+                    },
+                    None => quote! {
+                        Err(_) => {
+                            return Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+                                input,
+                                nom::error::ErrorKind::MapRes,
+                            )))
+                        }
+                    },
+                };
                 Some(quote! {
-                    let #name: #ty = #expr;
+                    let #name: #ty = match #expr {
+                        Ok(value) => value,
+                        #error_arm
+                    };
                 })
             }
+            FieldFormat::Default {
+                expr: Some(expr),
+                ty,
+                ..
+            } => Some(quote! {
+                let #name: #ty = #expr;
+            }),
+            FieldFormat::Default { expr: None, ty, .. } => Some(quote! {
+                let #name: #ty = Default::default();
+            }),
         }
     }
 
@@ -129,7 +462,7 @@ impl Fields {
     pub fn get_expression_names(&self) -> Vec<Ident> {
         self.fields_format
             .iter()
-            .filter(|field| !matches!(field, FieldFormat::Derived { .. }))
+            .filter(|field| field.is_parsed())
             .map(FieldFormat::get_param_name)
             .collect()
     }
@@ -137,18 +470,78 @@ impl Fields {
     pub fn get_expression_types(&self) -> Vec<Type> {
         self.fields_format
             .iter()
-            .filter(|field| !matches!(field, FieldFormat::Derived { .. }))
-            .map(|field| field.get_type().clone())
+            .filter(|field| field.is_parsed())
+            .map(FieldFormat::get_parsed_type)
             .collect()
     }
 
+    /// The statements that set the fields which are not parsed, after converting the parsed
+    /// fields to their own type. The default fields come first, followed by the derived fields in
+    /// the order of their dependencies, so derived fields can use each other. A cycle between
+    /// derived fields is turned into a `compile_error!`.
     pub fn get_derived_expressions(&self) -> Vec<TokenStream> {
+        let derived = match self.derived_order() {
+            Ok(derived) => derived,
+            Err(err) => return vec![err.to_compile_error()],
+        };
+
+        let conversions = self
+            .fields_format
+            .iter()
+            .filter_map(FieldFormat::generate_conversion);
+        let derived = self
+            .fields_format
+            .iter()
+            .filter(|field| matches!(field, FieldFormat::Default { .. }))
+            .chain(derived)
+            .filter_map(|field| field.generate_derived_expression(self));
+        conversions.chain(derived).collect()
+    }
+
+    /// Whether any of the conversions or derived fields can fail, which means that the parser has
+    /// to return early with an error.
+    pub fn has_fallible_derived(&self) -> bool {
+        self.fields_format.iter().any(|field| {
+            matches!(
+                field,
+                FieldFormat::Derived { fallible: true, .. }
+                    | FieldFormat::Expression {
+                        conversion: Some(Conversion::From(_)),
+                        ..
+                    }
+            )
+        })
+    }
+
+    /// The error types of the fallible derived fields that are passed on with `FromExternalError`.
+    pub fn get_external_error_types(&self) -> Vec<Type> {
         self.fields_format
             .iter()
-            .filter_map(|field| field.generate_derived_expression(self))
+            .filter_map(|field| match field {
+                FieldFormat::Derived {
+                    error: Some(error), ..
+                } => Some(error.clone()),
+                _ => None,
+            })
             .collect()
     }
 
+    /// Sorts the derived fields so that each one comes after the derived fields it refers to.
+    /// Otherwise, the fields keep their order.
+    fn derived_order(&self) -> Result<Vec<&FieldFormat>> {
+        let derived = self
+            .fields_format
+            .iter()
+            .filter(|field| matches!(field, FieldFormat::Derived { .. }))
+            .collect::<Vec<_>>();
+
+        let mut order = vec![];
+        for field in &derived {
+            visit_derived(field, &derived, &mut vec![], &mut order)?;
+        }
+        Ok(order)
+    }
+
     pub fn create_instance_expr(&self, variant_name: Option<&Ident>) -> TokenStream {
         let creation_names = self.get_creation_names();
 
@@ -174,28 +567,253 @@ impl Fields {
     }
 
     fn rename_derive_expr(&self, expr: &mut Expr) {
-        struct RenameDerived(HashMap<Ident, Ident>);
-        impl VisitMut for RenameDerived {
-            fn visit_path_mut(&mut self, path: &mut Path) {
-                for (source, target) in &self.0 {
-                    if path.is_ident(source) {
-                        path.segments = Punctuated::new();
-                        path.segments.push(syn::PathSegment {
-                            ident: target.clone(),
-                            arguments: Default::default(),
-                        });
-                    }
-                }
-            }
-        }
-
         let mapping = self
             .fields_format
             .iter()
-            .filter(|field| !matches!(field, FieldFormat::Derived { .. }))
             .map(|field| (field.get_name().clone(), field.get_param_name()))
             .collect();
 
-        RenameDerived(mapping).visit_expr_mut(expr)
+        rename_paths(expr, mapping);
+    }
+
+    /// Replaces the fields that are already parsed, given by their parameter names, with those
+    /// parameters in the format of a later field. Returns whether any field was referenced.
+    /// Converted fields can't be referenced, since they are only converted after all the fields
+    /// are parsed.
+    pub fn rename_parsed_fields(&self, expr: &mut Expr, parsed_names: &[Ident]) -> Result<bool> {
+        let parsed = self
+            .fields_format
+            .iter()
+            .filter(|field| parsed_names.contains(&field.get_param_name()))
+            .collect::<Vec<_>>();
+
+        let references = referenced_names(expr);
+        for field in &parsed {
+            let FieldFormat::Expression {
+                conversion: Some(_),
+                ..
+            } = field
+            else {
+                continue;
+            };
+            if let Some(reference) = references.iter().find(|name| *name == field.get_name()) {
+                return Err(syn::Error::new_spanned(
+                    reference,
+                    format!(
+                        "Field {} is converted with from or map after all the fields are parsed, \
+                         so a later format can not refer to it",
+                        reference
+                    ),
+                ));
+            }
+        }
+
+        let mapping = parsed
+            .into_iter()
+            .map(|field| (field.get_name().clone(), field.get_param_name()))
+            .collect();
+        Ok(rename_paths(expr, mapping))
+    }
+}
+
+/// Adds a derived field to the order after the derived fields it depends on. The path holds the
+/// fields that are being visited, so finding a field in there again means there is a cycle.
+fn visit_derived<'a>(
+    field: &'a FieldFormat,
+    derived: &[&'a FieldFormat],
+    path: &mut Vec<&'a FieldFormat>,
+    order: &mut Vec<&'a FieldFormat>,
+) -> Result<()> {
+    let name = field.get_name();
+    if order.iter().any(|done| done.get_name() == name) {
+        return Ok(());
+    }
+    if let Some(start) = path.iter().position(|visiting| visiting.get_name() == name) {
+        let cycle = path[start..]
+            .iter()
+            .chain([&field])
+            .map(|field| field.get_name().to_string())
+            .join(" -> ");
+        let FieldFormat::Derived { expr, .. } = path[start] else {
+            unreachable!("only derived fields are visited");
+        };
+        return Err(syn::Error::new_spanned(
+            expr,
+            format!(
+                "The derived fields depend on each other in a cycle: {}",
+                cycle
+            ),
+        ));
+    }
+
+    let FieldFormat::Derived { expr, .. } = field else {
+        unreachable!("only derived fields are visited");
+    };
+    let references = referenced_names(expr);
+    path.push(field);
+    for dependency in derived
+        .iter()
+        .filter(|other| references.contains(other.get_name()))
+    {
+        visit_derived(dependency, derived, path, order)?;
+    }
+    path.pop();
+    order.push(field);
+    Ok(())
+}
+
+/// Finds all the single identifiers in an expression, which could refer to fields.
+fn referenced_names(expr: &Expr) -> Vec<Ident> {
+    struct FindNames {
+        names: Vec<Ident>,
+        hidden: Vec<Ident>,
+    }
+    impl<'ast> Visit<'ast> for FindNames {
+        fn visit_path(&mut self, path: &'ast Path) {
+            if let Some(ident) = path.get_ident() {
+                if !self.hidden.contains(ident) {
+                    self.names.push(ident.clone());
+                }
+            }
+            syn::visit::visit_path(self, path);
+        }
+
+        fn visit_expr_closure(&mut self, closure: &'ast ExprClosure) {
+            let hidden = self.hidden.len();
+            self.hidden.extend(closure_parameters(closure));
+            syn::visit::visit_expr_closure(self, closure);
+            self.hidden.truncate(hidden);
+        }
+    }
+
+    let mut names = FindNames {
+        names: vec![],
+        hidden: vec![],
+    };
+    names.visit_expr(expr);
+    names.names
+}
+
+/// The names of the parameters of a closure, which hide the fields with the same name.
+fn closure_parameters(closure: &ExprClosure) -> Vec<Ident> {
+    struct FindBindings(Vec<Ident>);
+    impl<'ast> Visit<'ast> for FindBindings {
+        fn visit_pat_ident(&mut self, pat: &'ast PatIdent) {
+            self.0.push(pat.ident.clone());
+            syn::visit::visit_pat_ident(self, pat);
+        }
+    }
+
+    let mut bindings = FindBindings(vec![]);
+    for input in &closure.inputs {
+        bindings.visit_pat(input);
+    }
+    bindings.0
+}
+
+/// Replaces all the single identifier paths in the expression using the mapping, and returns
+/// whether anything was replaced.
+fn rename_paths(expr: &mut Expr, mapping: HashMap<Ident, Ident>) -> bool {
+    struct RenamePaths {
+        mapping: HashMap<Ident, Ident>,
+        hidden: Vec<Ident>,
+        renamed: bool,
+    }
+    impl VisitMut for RenamePaths {
+        fn visit_path_mut(&mut self, path: &mut Path) {
+            for (source, target) in &self.mapping {
+                if path.is_ident(source) && !self.hidden.contains(source) {
+                    path.segments = Punctuated::new();
+                    path.segments.push(syn::PathSegment {
+                        ident: target.clone(),
+                        arguments: Default::default(),
+                    });
+                    self.renamed = true;
+                }
+            }
+        }
+
+        fn visit_expr_closure_mut(&mut self, closure: &mut ExprClosure) {
+            let hidden = self.hidden.len();
+            self.hidden.extend(closure_parameters(closure));
+            syn::visit_mut::visit_expr_closure_mut(self, closure);
+            self.hidden.truncate(hidden);
+        }
+    }
+
+    let mut rename = RenamePaths {
+        mapping,
+        hidden: vec![],
+        renamed: false,
+    };
+    rename.visit_expr_mut(expr);
+    rename.renamed
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    fn struct_fields(mut item: syn::ItemStruct) -> Fields {
+        parse_fields(&mut item.fields).unwrap()
+    }
+
+    #[test]
+    fn test_derived_order() {
+        let fields = struct_fields(parse_quote!(
+            struct Test {
+                total: u32,
+                #[derived(half * 2)]
+                double: u32,
+                #[derived(total / 2)]
+                half: u32,
+            }
+        ));
+        let order = fields
+            .derived_order()
+            .unwrap()
+            .into_iter()
+            .map(|field| field.get_name().to_string())
+            .collect::<Vec<_>>();
+        assert_eq!(vec!["half", "double"], order);
+    }
+
+    #[test]
+    fn test_closure_hides_field() {
+        let fields = struct_fields(parse_quote!(
+            struct Test {
+                value: u32,
+                #[try_derived(u8::try_from(value).ok().filter(|percent| *percent <= 100).ok_or(()))]
+                percent: u8,
+            }
+        ));
+        assert_eq!(1, fields.derived_order().unwrap().len());
+
+        let mut expr = parse_quote!(value.map(|value| value + 1));
+        fields.rename_derive_expr(&mut expr);
+        let expected: Expr = parse_quote!(param_value.map(|value| value + 1));
+        assert_eq!(expected, expr);
+    }
+
+    #[test]
+    fn test_derived_cycle() {
+        let fields = struct_fields(parse_quote!(
+            struct Test {
+                #[derived(b + 1)]
+                a: u32,
+                #[derived(c + 1)]
+                b: u32,
+                #[derived(b + 1)]
+                c: u32,
+            }
+        ));
+        let Err(error) = fields.derived_order() else {
+            panic!("Expected a cycle");
+        };
+        assert_eq!(
+            "The derived fields depend on each other in a cycle: b -> c -> b",
+            error.to_string()
+        );
     }
 }
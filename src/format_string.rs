@@ -0,0 +1,247 @@
+use proc_macro2::Span;
+use std::ops::Range;
+use syn::{LitStr, Result};
+
+/// A part of the format string in a `match` format.
+pub enum FormatPart {
+    Literal(String),
+    Placeholder {
+        name: Option<String>,
+        spec: Option<String>,
+        span: Span,
+    },
+}
+
+/// Splits the format string of a `match` format into literal text and placeholders. Just like in
+/// the `format!` macro, `{{` and `}}` can be used to match a literal brace.
+pub fn parse_format_string(literal: &LitStr) -> Result<Vec<FormatPart>> {
+    let value = literal.value();
+    let mut parts = vec![];
+    let mut text = String::new();
+    let mut chars = value.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '{' if chars.next_if(|&(_, c)| c == '{').is_some() => text.push('{'),
+            '}' if chars.next_if(|&(_, c)| c == '}').is_some() => text.push('}'),
+            '}' => {
+                return Err(error_at(
+                    literal,
+                    index..index + 1,
+                    "Unmatched `}` in format string, use `}}` to match a literal `}`",
+                ));
+            }
+            '{' => {
+                let start = index + 1;
+                let Some(length) = find_placeholder_end(&value[start..]) else {
+                    return Err(error_at(
+                        literal,
+                        index..value.len(),
+                        "Unclosed placeholder in format string, use `{{` to match a literal `{`",
+                    ));
+                };
+                let end = start + length;
+
+                if !text.is_empty() {
+                    parts.push(FormatPart::Literal(std::mem::take(&mut text)));
+                }
+                parts.push(parse_placeholder(literal, &value, start..end)?);
+                while chars.next_if(|&(index, _)| index <= end).is_some() {}
+            }
+            c => text.push(c),
+        }
+    }
+
+    if !text.is_empty() {
+        parts.push(FormatPart::Literal(text));
+    }
+
+    Ok(parts)
+}
+
+fn parse_placeholder(literal: &LitStr, value: &str, range: Range<usize>) -> Result<FormatPart> {
+    let span = span_at(literal, range.start - 1..range.end + 1);
+    let content = &value[range.clone()];
+    let (name, spec) = match content.split_once(':') {
+        Some((name, spec)) => (name, Some(spec)),
+        None => (content, None),
+    };
+
+    let is_index = name.chars().all(|c| c.is_ascii_digit());
+    let is_ident = name.starts_with(|c: char| c.is_alphabetic() || c == '_')
+        && name.chars().all(|c| c.is_alphanumeric() || c == '_');
+    if !is_index && !is_ident {
+        return Err(error_at(
+            literal,
+            range.start..range.start + name.len(),
+            format!(
+                "Invalid placeholder `{{{}}}`, expected a field name or index",
+                content
+            ),
+        ));
+    }
+
+    let spec = match spec {
+        Some(spec) if spec.trim().is_empty() => {
+            return Err(error_at(
+                literal,
+                range,
+                format!("Missing parser after `:` in placeholder `{{{}}}`", content),
+            ));
+        }
+        spec => spec.map(|spec| spec.trim().to_string()),
+    };
+
+    Ok(FormatPart::Placeholder {
+        name: (!name.is_empty()).then(|| name.to_string()),
+        spec,
+        span,
+    })
+}
+
+/// Finds the closing brace of a placeholder, skipping nested braces and any braces that are
+/// part of string or character literals in a parser spec.
+fn find_placeholder_end(value: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut chars = value.char_indices().peekable();
+
+    while let Some((index, c)) = chars.next() {
+        match c {
+            '{' => depth += 1,
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            '"' => {
+                while let Some((_, c)) = chars.next() {
+                    match c {
+                        '\\' => {
+                            chars.next();
+                        }
+                        '"' => break,
+                        _ => {}
+                    }
+                }
+            }
+            '\'' => {
+                if let Some((_, '\\')) = chars.next() {
+                    chars.next();
+                }
+                chars.next_if(|&(_, c)| c == '\'');
+            }
+            _ => {}
+        }
+    }
+
+    None
+}
+
+fn error_at(literal: &LitStr, range: Range<usize>, message: impl std::fmt::Display) -> syn::Error {
+    let offset = literal.value()[..range.start].chars().count();
+    syn::Error::new(
+        span_at(literal, range),
+        format!("{} (at offset {} in the format)", message, offset),
+    )
+}
+
+/// Finds the span of a part of the string value. Pointing into a literal is only supported on
+/// nightly compilers, otherwise the span of the complete literal is returned.
+fn span_at(literal: &LitStr, range: Range<usize>) -> Span {
+    source_offsets(literal)
+        .and_then(|offsets| {
+            let start = *offsets.get(range.start)?;
+            let end = *offsets.get(range.end)?;
+            literal.token().subspan(start..end)
+        })
+        .unwrap_or_else(|| literal.span())
+}
+
+/// Maps every byte offset in the value of the literal to the byte offset in its source code.
+fn source_offsets(literal: &LitStr) -> Option<Vec<usize>> {
+    let source = literal.token().to_string();
+    let value_len = literal.value().len();
+
+    if source.starts_with('r') {
+        let start = source.find('"')? + 1;
+        return Some((0..=value_len).map(|offset| start + offset).collect());
+    }
+
+    let mut offsets = Vec::with_capacity(value_len + 1);
+    let mut chars = source.char_indices().skip(1).peekable();
+    while let Some((index, c)) = chars.next() {
+        let len = match c {
+            '"' => break,
+            '\\' => match chars.next()?.1 {
+                '\n' => {
+                    while chars.next_if(|&(_, c)| c.is_whitespace()).is_some() {}
+                    0
+                }
+                'x' => {
+                    chars.nth(1)?;
+                    1
+                }
+                'u' => {
+                    let mut hex = String::new();
+                    for (_, c) in chars.by_ref() {
+                        match c {
+                            '}' => break,
+                            '{' | '_' => {}
+                            c => hex.push(c),
+                        }
+                    }
+                    char::from_u32(u32::from_str_radix(&hex, 16).ok()?)?.len_utf8()
+                }
+                _ => 1,
+            },
+            c => c.len_utf8(),
+        };
+        offsets.resize(offsets.len() + len, index);
+    }
+    offsets.push(source.rfind('"')?);
+
+    (offsets.len() == value_len + 1).then_some(offsets)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(value: &str) -> Result<Vec<FormatPart>> {
+        parse_format_string(&LitStr::new(value, Span::call_site()))
+    }
+
+    fn error(value: &str) -> String {
+        parse(value).err().unwrap().to_string()
+    }
+
+    #[test]
+    fn test_escaped_braces() {
+        let parts = parse("{{\"a\": {a:delimited(\"{\", {}, \"}\")}}}").unwrap();
+        assert_eq!(3, parts.len());
+        assert!(matches!(&parts[0], FormatPart::Literal(text) if text == "{\"a\": "));
+        assert!(matches!(
+            &parts[1],
+            FormatPart::Placeholder { name: Some(name), spec: Some(spec), .. }
+                if name == "a" && spec == "delimited(\"{\", {}, \"}\")"
+        ));
+        assert!(matches!(&parts[2], FormatPart::Literal(text) if text == "}"));
+    }
+
+    #[test]
+    fn test_malformed_placeholders() {
+        assert_eq!(
+            "Invalid placeholder `{ }`, expected a field name or index (at offset 5 in the format)",
+            error("a {{{ }")
+        );
+        assert_eq!(
+            "Unmatched `}` in format string, use `}}` to match a literal `}` (at offset 3 in the format)",
+            error("{a}}")
+        );
+        assert_eq!(
+            "Unclosed placeholder in format string, use `{{` to match a literal `{` (at offset 1 in the format)",
+            error("({a")
+        );
+        assert_eq!(
+            "Missing parser after `:` in placeholder `{a: }` (at offset 2 in the format)",
+            error("x{a: }")
+        );
+    }
+}
@@ -0,0 +1,168 @@
+use crate::format_string::{parse_format_string, FormatPart};
+use crate::parse_format::ParseFormat;
+use crate::parse_options::ParseOptions;
+use crate::parsed_item::ParsedVariant;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use std::collections::BTreeMap;
+use syn::{Expr, ExprLit, Lit, LitByte};
+
+/// A node in the trie of keywords, where every edge is a single byte.
+#[derive(Default)]
+struct TrieNode<'a> {
+    variant: Option<&'a Ident>,
+    children: BTreeMap<u8, TrieNode<'a>>,
+}
+
+/// Finds the keywords of a unit variant whose format is just a literal or an `alt` of literals,
+/// together with whether they are matched case-insensitively.
+fn variant_keywords(
+    variant: &ParsedVariant,
+    options: &ParseOptions,
+) -> Option<(Vec<Vec<u8>>, bool)> {
+    if !variant.fields.fields_format.is_empty() {
+        return None;
+    }
+
+    let case_insensitive = variant.options.inherit(options).case_insensitive;
+    match &variant.format {
+        ParseFormat::Expr(expr) => Some((expr_keywords(expr)?, case_insensitive)),
+        ParseFormat::Match(format) if !format.whitespace => {
+            let mut keyword = vec![];
+            for part in parse_format_string(&format.literal).ok()? {
+                match part {
+                    FormatPart::Literal(text) => keyword.extend(text.into_bytes()),
+                    FormatPart::Placeholder { .. } => return None,
+                }
+            }
+            Some((vec![keyword], case_insensitive || format.case_insensitive))
+        }
+        _ => None,
+    }
+}
+
+/// Finds the keywords of a format that is a literal or an `alt` of literals.
+pub fn expr_keywords(expr: &Expr) -> Option<Vec<Vec<u8>>> {
+    match expr {
+        Expr::Lit(ExprLit { lit, .. }) => Some(vec![literal_bytes(lit)?]),
+        Expr::Call(call) if matches!(call.func.as_ref(), Expr::Path(path) if path.path.is_ident("alt")) =>
+        {
+            let args = match call.args.first() {
+                Some(Expr::Tuple(tuple)) if call.args.len() == 1 => tuple.elems.iter().collect(),
+                _ => call.args.iter().collect::<Vec<_>>(),
+            };
+            args.into_iter()
+                .map(|arg| match arg {
+                    Expr::Lit(ExprLit { lit, .. }) => literal_bytes(lit),
+                    _ => None,
+                })
+                .collect()
+        }
+        _ => None,
+    }
+}
+
+fn literal_bytes(lit: &Lit) -> Option<Vec<u8>> {
+    match lit {
+        Lit::Str(value) => Some(value.value().into_bytes()),
+        Lit::ByteStr(value) => Some(value.value()),
+        Lit::Byte(value) => Some(vec![value.value()]),
+        Lit::Char(value) => Some(value.value().to_string().into_bytes()),
+        _ => None,
+    }
+}
+
+/// Generates a parser that selects the variant with the longest matching keyword, regardless of
+/// the order of the variants. This is only possible when all the variants are unit variants with
+/// a literal format, which are all matched with the same case sensitivity. Otherwise `None` is
+/// returned.
+pub fn generate_trie_dispatch(
+    variants: &[&ParsedVariant],
+    fallback: Option<&TokenStream>,
+    options: &ParseOptions,
+) -> Option<TokenStream> {
+    let keywords = variants
+        .iter()
+        .map(|variant| variant_keywords(variant, options))
+        .collect::<Option<Vec<_>>>()?;
+    let case_insensitive = keywords.first()?.1;
+    if keywords.iter().any(|(_, other)| *other != case_insensitive) {
+        return None;
+    }
+
+    let mut root = TrieNode::default();
+    for ((variant_keywords, _), variant) in keywords.into_iter().zip(variants) {
+        for keyword in variant_keywords {
+            let mut node = &mut root;
+            for byte in keyword {
+                let byte = if case_insensitive {
+                    byte.to_ascii_lowercase()
+                } else {
+                    byte
+                };
+                node = node.children.entry(byte).or_default();
+            }
+            // Just like with `alt`, the first variant wins when two variants have the same keyword
+            node.variant.get_or_insert(&variant.name);
+        }
+    }
+
+    let root_match = root
+        .variant
+        .map(|variant| quote! { found = Some((0, Self::#variant)); });
+    let trie = generate_node(&root, 0, case_insensitive);
+    let no_match = match fallback {
+        Some(fallback) => quote! { #fallback.parse(input) },
+        None => quote! {
+            Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+                input,
+                nom::error::ErrorKind::Tag,
+            )))
+        },
+    };
+
+    Some(quote! {
+        let bytes = input.as_bytes();
+        let mut found = None;
+        #root_match
+        #trie
+        match found {
+            Some((length, value)) => Ok((input.take_split(length).0, value)),
+            None => #no_match,
+        }
+    })
+}
+
+fn generate_node(node: &TrieNode, depth: usize, case_insensitive: bool) -> TokenStream {
+    if node.children.is_empty() {
+        return TokenStream::new();
+    }
+
+    let arms = node.children.iter().map(|(&byte, child)| {
+        let lower = LitByte::new(byte, Span::call_site());
+        let upper = LitByte::new(byte.to_ascii_uppercase(), Span::call_site());
+        let pattern = if case_insensitive && byte.is_ascii_lowercase() {
+            quote! { #lower | #upper }
+        } else {
+            quote! { #lower }
+        };
+        let found = child
+            .variant
+            .map(|variant| quote! { found = Some((#depth + 1, Self::#variant)); });
+        let children = generate_node(child, depth + 1, case_insensitive);
+
+        quote! {
+            Some(#pattern) => {
+                #found
+                #children
+            }
+        }
+    });
+
+    quote! {
+        match bytes.get(#depth).copied() {
+            #(#arms)*
+            _ => {}
+        }
+    }
+}
@@ -17,10 +17,14 @@
 //! don't need a ton of use statements in your code. But there are also a couple of special cases:
 //!
 //! - `{}` or `()` will be replaced with a [`nom_parse_trait::ParseFrom::parse`] call for the
-//! corresponding field. This is useful when you are using types that have implemented the
-//! `ParseFrom` trait already.
+//!   corresponding field. This is useful when you are using types that have implemented the
+//!   `ParseFrom` trait already.
 //! - Strings, bytes strings and characters will be translated to match the input verbatim using
-//! the [`nom::bytes::complete::tag`] function.
+//!   the [`nom::bytes::complete::tag`] function.
+//! - `regex("...")` matches the start of the input with a regular expression and returns the
+//!   longest matched part of the input. The regex is checked when the macro is expanded and
+//!   compiled only once at runtime. This requires the `regex-automata` crate as a dependency of
+//!   your own crate.
 //!
 //! # Input types that are supported
 //!
@@ -34,22 +38,30 @@
 //! # Known limitations
 //!
 //! - When your try to use a custom parser combinator, the nom function parser will try to change
-//! all parameters to be nom parsers. This is useful in many cases, but when you need to pass in
-//! a normal string for example, it won't work. In these cases, you can define a separate function
-//! to wrap the call. I'm not sure how to fix that right now, but I'm open to suggestions.
+//!   all parameters to be nom parsers. This is useful in many cases, but when you need to pass in
+//!   a normal string for example, it won't work. In these cases, you can define a separate function
+//!   to wrap the call, and use it with the `#[parse_with(...)]` attribute on a field or variant.
+//!   The function given there is used verbatim.
 //!
 //! - Since the generated input type is very generic, all functions that you want to use in the
-//! nom expression should also be very generic. In the future I might add a way to specify if you
-//! want to generate a specific input type, but for now it's not possible.
+//!   nom expression should also be very generic. In the future I might add a way to specify if you
+//!   want to generate a specific input type, but for now it's not possible.
 
 extern crate proc_macro;
+mod display_generator;
 mod fields;
+mod format_string;
+mod keyword_trie;
 mod nom_packages;
 mod parse_format;
+mod parse_options;
 mod parsed_item;
 mod parser_generator;
+mod rename_rule;
+mod shadowing;
+mod write_generator;
 
-use crate::parse_format::ParseFormat;
+use crate::parse_format::ParseAttribute;
 use crate::parsed_item::ParsedItem;
 use crate::parser_generator::ParserGenerator;
 use proc_macro::TokenStream;
@@ -94,11 +106,129 @@ use quote::ToTokens;
 /// }
 /// ```
 ///
+/// An enum can have a format as well, which is used around the parser for the variants. In this
+/// format, the `{}` placeholder stands for one of the variants.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(delimited("[", {}, "]"))]
+/// enum Value {
+///     Number(u32),
+///     #[format("none")]
+///     Nothing,
+/// }
+/// ```
+///
+/// When all the variants are unit variants with a literal format, the variant with the longest
+/// matching keyword is selected, regardless of the order of the variants. Here `"int"` is parsed
+/// as `Int`, even though `In` comes first.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from]
+/// enum Keyword {
+///     #[format("in")]
+///     In,
+///     #[format("int")]
+///     Int,
+/// }
+/// ```
+///
+/// ## Variant order
+///
+/// Variants are tried in the order they are declared, so a variant can never be parsed when an
+/// earlier variant always matches first, like `"a"` before `"ab"`. When this can be seen from
+/// the literal text in the formats, it is reported as a `deprecated` warning at the shadowed
+/// variant. Variants with a higher `#[priority(...)]` are tried first, so the order can be changed
+/// without reordering the enum.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from]
+/// enum Value {
+///     #[format("a")]
+///     A,
+///     #[priority(1)]
+///     #[format(match "ab{}")]
+///     Ab(u32),
+/// }
+/// ```
+///
+/// ## Errors of all variants
+///
+/// When no variant matches, nom only returns the error of the last variant that was tried. With
+/// the `all_errors` option, the errors of all the variants are combined with `ParseError::or`
+/// instead, each with the name of its variant added as context. This needs an error type that
+/// implements `ContextError`. The errors are combined in order of how far each variant got, so
+/// error types that keep only one error, like `nom::error::Error`, return the error of the variant
+/// that got the furthest. To find out how far a variant got, it is parsed with a hidden error type
+/// `<Enum>ErrorProgress` that wraps your own error type. The `tag` and `repr` options only try a
+/// single variant, so they can not be combined with this option.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(all_errors)]
+/// enum Command {
+///     #[format(match "set {}")]
+///     Set(u32),
+///     #[format(match "get {}")]
+///     Get(u32),
+/// }
+/// ```
+///
+/// ## Keywords
+///
+/// With the `keywords` option, unit variants without a format match their own name. The
+/// `rename_all` option changes those names to `"snake_case"`, `"kebab-case"`, `"lowercase"` or
+/// `"SCREAMING_SNAKE_CASE"` first. A single variant can be given another keyword with
+/// `#[rename("...")]`, and extra keywords with `#[alias("...")]`.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(keywords, rename_all = "snake_case")]
+/// enum Setting {
+///     MaxSize,
+///     #[rename("min")]
+///     MinSize,
+///     #[alias("dir")]
+///     Directory,
+/// }
+/// ```
+///
+/// ## Skipped and fallback variants
+///
+/// A variant with `#[skip]` is never produced by the parser, so it needs no format. Since it has
+/// no text to write either, it can't be used together with the `display` option. The variant
+/// with `#[fallback]` is produced when none of the other variants match. A unit fallback consumes
+/// no input, while `#[fallback(token)]` captures everything up to the next whitespace and
+/// `#[fallback(line)]` captures the rest of the line into its single field. The captured text is
+/// converted with `From<String>`.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(keywords)]
+/// enum Command {
+///     Start,
+///     Stop,
+///     #[skip]
+///     Internal,
+///     #[fallback(token)]
+///     Unknown(String),
+/// }
+/// ```
+///
 /// ## Derived fields
 ///
 /// Sometimes it's useful to have a field that is not actually parsed, but derived from the other
 /// fields. This can be done with the `#[derived]` attribute. In this example, we derive the sum of
-/// the two fields `x` and `y`.
+/// the two fields `x` and `y`. A derived field can also use other derived fields, as long as they
+/// don't depend on each other in a cycle.
 ///
 /// ```rust
 /// use nom_parse_macros::parse_from;
@@ -112,6 +242,153 @@ use quote::ToTokens;
 /// }
 /// ```
 ///
+/// When deriving a field can fail, use `#[try_derived(...)]` with an expression that returns a
+/// `Result`. An `Err` makes the parser fail with `ErrorKind::MapRes`. With
+/// `#[try_derived(..., error = Type)]`, the error is passed on with `FromExternalError`, which the
+/// error type of the parser then has to implement for that type.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(match "{}")]
+/// struct Percentage {
+///     value: u32,
+///     #[try_derived(u8::try_from(value).ok().filter(|percent| *percent <= 100).ok_or(()))]
+///     percent: u8,
+/// }
+/// ```
+///
+/// ## Default fields
+///
+/// Fields with the `#[default]` attribute are not parsed, but set to `Default::default()`, or to
+/// the expression given with `#[default = ...]`. `PhantomData` fields are never parsed, so they
+/// don't need the attribute. Neither kind of field takes part in the format.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+/// use std::marker::PhantomData;
+///
+/// #[parse_from(separated_pair({}, ",", {}))]
+/// struct Point<Unit> {
+///     x: i32,
+///     y: i32,
+///     #[default]
+///     visited: bool,
+///     #[default = "origin"]
+///     label: &'static str,
+///     unit: PhantomData<Unit>,
+/// }
+/// ```
+///
+/// ## Field formats
+///
+/// When the format is left out, the fields are parsed in order. Each field can have its own
+/// format with the `#[format]` attribute, otherwise its `ParseFrom` implementation is used. This
+/// also works for the fields of enum variants. In a `match` format, a placeholder without a parser
+/// uses the format of its field.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from]
+/// struct Color {
+///     #[format(preceded("#", hex_u32))]
+///     rgb: u32,
+///     #[format(preceded(space1, {}))]
+///     alpha: u32,
+/// }
+/// ```
+///
+/// The format of a field can use the fields that are parsed before it, like a length that is
+/// followed by that many items. In a `match` format, these are the fields of the earlier
+/// placeholders.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from]
+/// struct Packet {
+///     #[format(be_u16)]
+///     len: u16,
+///     #[format(count(be_u8, len as usize))]
+///     items: Vec<u8>,
+/// }
+/// ```
+///
+/// When a field or variant should use a hand-written parser function, it can be given with the
+/// `#[parse_with(...)]` attribute. Unlike a format, the path is used as is, so nothing is turned
+/// into a parser.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(match "{}x{}")]
+/// struct Size {
+///     #[parse_with(nom::character::complete::u32)]
+///     width: u32,
+///     #[parse_with(nom::character::complete::u32)]
+///     height: u32,
+/// }
+/// ```
+///
+/// ## Field conversions
+///
+/// A field can be parsed as another type and converted afterwards. With `#[from(Type)]`, the
+/// field is parsed as the given type and converted with `TryFrom`, which also covers all types
+/// that implement `From`. A failed conversion gives an `ErrorKind::MapRes` error. With
+/// `#[map(path)]`, the parsed value is converted with the given function. The conversions run
+/// after all the fields are parsed, so the formats of later fields can't refer to a converted
+/// field, although derived fields can.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// struct Meters(u64);
+///
+/// impl From<u32> for Meters {
+///     fn from(value: u32) -> Self {
+///         Meters(value as u64)
+///     }
+/// }
+///
+/// fn double(value: u32) -> u64 {
+///     value as u64 * 2
+/// }
+///
+/// #[parse_from(match "{} {}")]
+/// struct Distance {
+///     #[from(u32)]
+///     length: Meters,
+///     #[map(double)]
+///     doubled: u64,
+/// }
+/// ```
+///
+/// ## Verification
+///
+/// A parsed field can be checked with `#[verify(...)]`, where the name of the field is a
+/// reference to the parsed value. The whole item can be checked with the `verify` option, which
+/// takes a function that gets a reference to the item. When a check returns `false`, the parser
+/// fails with `ErrorKind::Verify` at the start of the field or item.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(match "{}-{}", verify = Self::is_small)]
+/// struct Range {
+///     #[verify(*start <= 255)]
+///     start: u32,
+///     #[verify(*end >= start)]
+///     end: u32,
+/// }
+///
+/// impl Range {
+///     fn is_small(&self) -> bool {
+///         self.end - self.start < 10
+///     }
+/// }
+/// ```
+///
 /// ## Match verbatim
 ///
 /// This example shows how to match a string verbatim. This is useful when you have a very simple
@@ -127,13 +404,167 @@ use quote::ToTokens;
 ///   y: f32,
 /// }
 /// ```
-
+///
+/// Placeholders can also name the field they match, so the format doesn't need to follow the
+/// declaration order. For tuple structs, the index of the field is used as the name. Just like
+/// with the `format!` macro, use `{{` and `}}` to match literal braces.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(match "{y},{x}")]
+/// struct Point {
+///   x: u32,
+///   y: u32,
+/// }
+///
+/// #[parse_from(match "{1}: {0}")]
+/// struct Entry(u32, i64);
+/// ```
+///
+/// By default a placeholder uses the `ParseFrom` implementation of the field. A different parser
+/// can be given after a colon, which is handled just like any other nom expression.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(match "#{:hex_u32} [{values:separated_list0(\",\", {})}]")]
+/// struct Palette {
+///   color: u32,
+///   values: Vec<u32>,
+/// }
+/// ```
+///
+/// With `match ws`, any amount of whitespace is accepted between the tokens of the format. The
+/// `whitespace` option does the same, but with a custom parser for the whitespace.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(match ws "({}, {})")]
+/// struct Vector {
+///   x: f32,
+///   y: f32,
+/// }
+///
+/// #[parse_from(match "{} = {}", whitespace = space0)]
+/// struct Setting {
+///   key: u32,
+///   value: u32,
+/// }
+/// ```
+///
+/// ## Case-insensitive matching
+///
+/// Literal text is matched case-insensitively with `match nocase`. For other formats, the
+/// `case_insensitive` option makes all string, byte and character literals case-insensitive, as
+/// well as explicit `tag(...)` and `char(...)` parsers. On an enum, this applies to the formats of
+/// all the variants.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(case_insensitive)]
+/// enum Keyword {
+///     #[format("select")]
+///     Select,
+///     #[format(match "limit {}")]
+///     Limit(u32),
+/// }
+///
+/// #[parse_from(match nocase "from {}")]
+/// struct From(u32);
+/// ```
+///
+/// ## Display
+///
+/// With the `display` option, a `Display` implementation is generated from the `match` format, so
+/// printing and parsing use the same layout. Literal text is written verbatim and every
+/// placeholder is written with the `Display` implementation of its field. Since that only matches
+/// the parser when the field is parsed with its `ParseFrom` implementation, placeholders with a
+/// format and fields with `#[format]`, `#[parse_with]`, `#[verify]`, `#[from]` or `#[map]` are a
+/// compile error. On an enum, each variant is written with its own `match` format or string
+/// literal.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(match "({}, {})", display)]
+/// struct Vector {
+///     x: f32,
+///     y: f32,
+/// }
+///
+/// assert_eq!("(1.5, 2)", Vector { x: 1.5, y: 2.0 }.to_string());
+/// ```
+///
+/// ## Binary formats
+///
+/// The `be_*` and `le_*` number parsers only work on byte input, so a parser that uses them is
+/// only implemented for inputs like `&[u8]`. With the `write` option, the `write_to` and
+/// `to_bytes` methods are generated as well, which write the struct in the same format. This
+/// works for formats that consist of number parsers, literals, placeholders, and sequences of
+/// those like `preceded` or `delimited`. Fields that use their `ParseFrom` implementation are
+/// written with their own `write_to` method.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from((be_u16, preceded(b"\x00", le_u32)), write)]
+/// struct Header {
+///     kind: u16,
+///     length: u32,
+/// }
+///
+/// let header = Header { kind: 1, length: 2 };
+/// assert_eq!(b"\x00\x01\x00\x02\x00\x00\x00", header.to_bytes().as_slice());
+/// ```
+///
+/// ## Tagged enums
+///
+/// Instead of trying every variant in order, an enum can read a tag first with the `tag` option,
+/// and then only parse the variant that has a matching `#[tag(...)]` pattern. When no variant
+/// matches, parsing fails with a `Switch` error, unless there is a variant with `#[tag(_)]`. That
+/// variant is parsed from the start of the input, so it can include the tag itself.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(tag = be_u8)]
+/// enum Message {
+///     #[tag(0x01)]
+///     Ping,
+///     #[tag(0x02)]
+///     #[format(be_u32)]
+///     Data(u32),
+///     #[tag(_)]
+///     #[format(be_u8)]
+///     Unknown(u8),
+/// }
+/// ```
+///
+/// For a fieldless enum with a `#[repr(...)]` attribute, the `repr` option reads a number of that
+/// type and selects the variant with that discriminant. The number is read as big endian, unless
+/// the `le` option is given.
+///
+/// ```rust
+/// use nom_parse_macros::parse_from;
+///
+/// #[parse_from(repr, le)]
+/// #[repr(u16)]
+/// enum Opcode {
+///     Ping = 1,
+///     Pong,
+///     Close = 0x100,
+/// }
+/// ```
 #[proc_macro_attribute]
 pub fn parse_from(attrs: TokenStream, object: TokenStream) -> TokenStream {
-    let parse_format = syn::parse_macro_input!(attrs as ParseFormat);
+    let attribute = syn::parse_macro_input!(attrs as ParseAttribute);
     let parsed_item = syn::parse_macro_input!(object as ParsedItem);
 
-    ParserGenerator::new(parse_format, parsed_item)
-        .to_token_stream()
-        .into()
+    match ParserGenerator::new(attribute, parsed_item) {
+        Ok(generator) => generator.to_token_stream().into(),
+        Err(err) => err.to_compile_error().into(),
+    }
 }
@@ -1,9 +1,10 @@
 use crate::parse_format::generate_match_literal;
-use quote::quote_spanned;
+use crate::parse_options::ParseOptions;
+use proc_macro2::{TokenStream, TokenTree};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::{
-    parse, parse_quote_spanned, parse_str, Expr, ExprCall, ExprPath, ExprTuple, Lit, Path, Result,
+    parse_quote_spanned, parse_str, Expr, ExprCall, ExprLit, ExprPath, ExprTuple, Lit, Path, Result,
 };
 
 const NOM_FUNCTIONS: phf::Map<&'static str, (&'static str, &'static [bool])> = phf::phf_map! {
@@ -142,14 +143,26 @@ const NOM_FUNCTIONS: phf::Map<&'static str, (&'static str, &'static [bool])> = p
     "tuple" => ("nom::sequence::tuple", &[]), // Special handling for tuples
 };
 
-pub fn update_nom_expression(expr: &mut Expr) -> Result<()> {
+/// Checks if the generated parser uses any of the binary number parsers, which only work on input
+/// that consists of bytes.
+pub fn uses_byte_parsers(tokens: &TokenStream) -> bool {
+    tokens.clone().into_iter().any(|token| match token {
+        TokenTree::Group(group) => uses_byte_parsers(&group.stream()),
+        TokenTree::Ident(ident) => {
+            let name = ident.to_string();
+            (name.starts_with("be_") || name.starts_with("le_"))
+                && NOM_FUNCTIONS.contains_key(&name)
+        }
+        _ => false,
+    })
+}
+
+pub fn update_nom_expression(expr: &mut Expr, options: &ParseOptions) -> Result<()> {
     match expr {
         Expr::Block(block_expr) => {
             if block_expr.block.stmts.is_empty() {
-                *expr = parse::<Expr>(
-                    quote_spanned! { block_expr.span() => nom_parse_trait::ParseFrom::parse }
-                        .into(),
-                )?;
+                *expr =
+                    parse_quote_spanned! { block_expr.span() => nom_parse_trait::ParseFrom::parse };
                 Ok(())
             } else {
                 Err(syn::Error::new_spanned(
@@ -158,22 +171,30 @@ pub fn update_nom_expression(expr: &mut Expr) -> Result<()> {
                 ))
             }
         }
-        Expr::Call(call) => parse_call(call),
+        Expr::Call(call) if is_regex_call(call) => {
+            *expr = generate_regex_parser(call)?;
+            Ok(())
+        }
+        Expr::Call(call) => parse_call(call, options),
         Expr::Lit(lit_expr) => match &lit_expr.lit {
             Lit::Str(value) => {
-                *expr = generate_match_literal(value.value().as_bytes(), value.span());
+                *expr = generate_match_literal(value.value().as_bytes(), value.span(), options);
                 Ok(())
             }
             Lit::ByteStr(value) => {
-                *expr = generate_match_literal(&value.value(), value.span());
+                *expr = generate_match_literal(&value.value(), value.span(), options);
                 Ok(())
             }
             Lit::Byte(value) => {
-                *expr = generate_match_literal(&[value.value()], value.span());
+                *expr = generate_match_literal(&[value.value()], value.span(), options);
                 Ok(())
             }
             Lit::Char(value) => {
-                *expr = generate_match_literal(value.value().to_string().as_bytes(), value.span());
+                *expr = generate_match_literal(
+                    value.value().to_string().as_bytes(),
+                    value.span(),
+                    options,
+                );
                 Ok(())
             }
             _ => Err(syn::Error::new_spanned(
@@ -189,7 +210,7 @@ pub fn update_nom_expression(expr: &mut Expr) -> Result<()> {
             } else {
                 // Tuples are assumed to be all parsers
                 for elem in elems.iter_mut() {
-                    update_nom_expression(elem)?;
+                    update_nom_expression(elem, options)?;
                 }
             }
             Ok(())
@@ -201,12 +222,28 @@ pub fn update_nom_expression(expr: &mut Expr) -> Result<()> {
     }
 }
 
-fn parse_call(call: &mut ExprCall) -> Result<()> {
+fn parse_call(call: &mut ExprCall, options: &ParseOptions) -> Result<()> {
     if let Expr::Path(ExprPath { path, .. }) = call.func.as_mut() {
         if path.segments.len() == 1 {
-            let ident = path.segments[0].ident.to_string();
+            let mut ident = path.segments[0].ident.to_string();
             let arguments = path.segments[0].arguments.clone();
 
+            // With the case_insensitive option, explicit tags and characters are matched
+            // case-insensitively as well, just like literals
+            if options.case_insensitive && ident == "tag" {
+                ident = "tag_no_case".to_string();
+            }
+            if options.case_insensitive && ident == "char" && call.args.len() == 1 {
+                let expected = &call.args[0];
+                *call = parse_quote_spanned! { call.span() =>
+                    nom::character::complete::satisfy({
+                        let expected: char = #expected;
+                        move |c: char| c.to_lowercase().eq(expected.to_lowercase())
+                    })
+                };
+                return Ok(());
+            }
+
             if let Some(&(nom_path, parameters)) = NOM_FUNCTIONS.get(ident.as_str()) {
                 path.segments = parse_str::<Path>(nom_path)?.segments;
                 path.segments.last_mut().unwrap().arguments = arguments;
@@ -216,7 +253,7 @@ fn parse_call(call: &mut ExprCall) -> Result<()> {
                 if ident == "tuple" || ident == "alt" {
                     let args = call.args.clone();
                     if args.len() != 1 {
-                        call.args = Punctuated::from(Punctuated::new());
+                        call.args = Punctuated::new();
                         call.args.push(Expr::Tuple(ExprTuple {
                             attrs: vec![],
                             paren_token: Default::default(),
@@ -225,10 +262,10 @@ fn parse_call(call: &mut ExprCall) -> Result<()> {
                     }
 
                     for arg in call.args.iter_mut() {
-                        update_nom_expression(arg)?;
+                        update_nom_expression(arg, options)?;
                     }
                 // Nom functions without parameters should not be called, but referenced directly
-                } else if parameters.len() == 0 {
+                } else if parameters.is_empty() {
                     if ident != "fail" {
                         return Err(syn::Error::new_spanned(
                             call.func.clone(),
@@ -249,14 +286,14 @@ fn parse_call(call: &mut ExprCall) -> Result<()> {
                 } else {
                     for (arg, &is_parser) in call.args.iter_mut().zip(parameters) {
                         if is_parser {
-                            update_nom_expression(arg)?;
+                            update_nom_expression(arg, options)?;
                         }
                     }
                 }
             } else {
                 // Assume that this is a custom function, for which all parameters need to be parsed as parsers
                 for arg in call.args.iter_mut() {
-                    update_nom_expression(arg)?;
+                    update_nom_expression(arg, options)?;
                 }
             }
         }
@@ -270,6 +307,66 @@ fn parse_call(call: &mut ExprCall) -> Result<()> {
     }
 }
 
+fn is_regex_call(call: &ExprCall) -> bool {
+    matches!(call.func.as_ref(), Expr::Path(ExprPath { path, .. }) if path.is_ident("regex"))
+}
+
+/// Generates a parser for `regex("...")`, which returns the longest part of the input that
+/// matches the regex at the start of the input. The regex is checked while expanding the macro
+/// with the same syntax options as the runtime regex, which is compiled once when it is first
+/// used. Both use the default UTF-8 mode, so a match never ends inside a character.
+fn generate_regex_parser(call: &ExprCall) -> Result<Expr> {
+    let pattern = match call.args.first() {
+        Some(Expr::Lit(ExprLit {
+            lit: Lit::Str(pattern),
+            ..
+        })) if call.args.len() == 1 => pattern,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                call,
+                "The regex function expects a single string literal",
+            ))
+        }
+    };
+
+    if let Err(err) = regex_syntax::Parser::new().parse(&pattern.value()) {
+        return Err(syn::Error::new_spanned(
+            pattern,
+            format!("Invalid regex: {}", err),
+        ));
+    }
+
+    Ok(parse_quote_spanned! { call.span() =>
+        |input: I| -> nom::IResult<I, I, _> {
+            use regex_automata::meta::Regex;
+            use regex_automata::util::lazy::Lazy;
+
+            static REGEX: Lazy<Regex> = Lazy::new(|| {
+                Regex::builder()
+                    .configure(
+                        Regex::config()
+                            .match_kind(regex_automata::MatchKind::All)
+                            .nfa_size_limit(None),
+                    )
+                    .build(#pattern)
+                    .expect("the regex is checked when the macro is expanded")
+            });
+            let search = regex_automata::Input::new(input.as_bytes())
+                .anchored(regex_automata::Anchored::Yes);
+            match REGEX.search_half(&search) {
+                Some(found) => {
+                    let (rest, matched) = input.take_split(found.offset());
+                    Ok((rest, matched))
+                }
+                None => Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+                    input,
+                    nom::error::ErrorKind::RegexpMatch,
+                ))),
+            }
+        }
+    })
+}
+
 fn parse_path(path_expr: &mut Path) -> Result<()> {
     if path_expr.segments.len() == 1 {
         let ident = path_expr.segments[0].ident.to_string();
@@ -293,3 +390,31 @@ fn parse_path(path_expr: &mut Path) -> Result<()> {
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use syn::parse_quote;
+
+    #[test]
+    fn test_invalid_regex() {
+        let mut expr: Expr = parse_quote!(regex("[a-z"));
+        let error = update_nom_expression(&mut expr, &ParseOptions::default()).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid regex: "));
+
+        // Matching a part of a character could split `&str` input inside that character
+        let mut expr: Expr = parse_quote!(regex(r"(?-u:\xC3)"));
+        let error = update_nom_expression(&mut expr, &ParseOptions::default()).unwrap_err();
+        assert!(error.to_string().starts_with("Invalid regex: "));
+    }
+
+    #[test]
+    fn test_regex_arguments() {
+        let mut expr: Expr = parse_quote!(regex(alpha1));
+        let error = update_nom_expression(&mut expr, &ParseOptions::default()).unwrap_err();
+        assert_eq!(
+            "The regex function expects a single string literal",
+            error.to_string()
+        );
+    }
+}
@@ -1,24 +1,66 @@
+use crate::fields::{FieldFormat, Fields};
+use crate::format_string::{parse_format_string, FormatPart};
 use crate::nom_packages::update_nom_expression;
-use proc_macro2::Span;
+use crate::parse_options::ParseOptions;
+use proc_macro2::{Ident, Span};
 use quote::ToTokens;
 use syn::parse::{Parse, ParseStream};
+use syn::spanned::Spanned;
+use syn::visit_mut::{self, VisitMut};
 use syn::{parse_quote, parse_quote_spanned, ExprBlock};
 
 #[derive(Debug, PartialEq)]
 pub enum ParseFormat {
-    Match(syn::LitStr),
+    Match(MatchFormat),
     Expr(syn::Expr),
+    /// A parser function given with `#[parse_with(...)]`, which is used verbatim.
+    With(syn::Path),
     Default,
 }
 
+#[derive(Debug, PartialEq)]
+pub struct MatchFormat {
+    pub literal: syn::LitStr,
+    /// Set with `match ws "..."`, which allows whitespace between all the tokens.
+    pub whitespace: bool,
+    /// Set with `match nocase "..."`, which matches all the literal text case-insensitively.
+    pub case_insensitive: bool,
+}
+
+/// The complete contents of a `parse_from` or `format` attribute: the format itself followed by
+/// any options.
+pub struct ParseAttribute {
+    pub format: ParseFormat,
+    pub options: ParseOptions,
+}
+
 impl Parse for ParseFormat {
     fn parse(input: ParseStream) -> syn::Result<Self> {
-        if input.is_empty() {
+        if input.is_empty() || ParseOptions::peek_option(input) {
             Ok(ParseFormat::Default)
         } else if input.peek(syn::Token![match]) {
             let _keyword = input.parse::<syn::Token![match]>()?;
+            let mut whitespace = false;
+            let mut case_insensitive = false;
+            while input.peek(syn::Ident) {
+                let modifier = input.parse::<syn::Ident>()?;
+                if modifier == "ws" {
+                    whitespace = true;
+                } else if modifier == "nocase" {
+                    case_insensitive = true;
+                } else {
+                    return Err(syn::Error::new_spanned(
+                        &modifier,
+                        format!("Unknown match modifier {}", modifier),
+                    ));
+                }
+            }
             let literal = input.parse::<syn::LitStr>()?;
-            Ok(ParseFormat::Match(literal))
+            Ok(ParseFormat::Match(MatchFormat {
+                literal,
+                whitespace,
+                case_insensitive,
+            }))
         } else {
             let expr = input.parse::<syn::Expr>()?;
             Ok(ParseFormat::Expr(expr))
@@ -26,85 +68,467 @@ impl Parse for ParseFormat {
     }
 }
 
-impl ToTokens for ParseFormat {
-    fn to_tokens(&self, tokens: &mut proc_macro2::TokenStream) {
-        match self.to_expr() {
-            Ok(expr) => expr.to_tokens(tokens),
-            Err(err) => err.to_compile_error().to_tokens(tokens),
+impl Parse for ParseAttribute {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let format = input.parse::<ParseFormat>()?;
+        if format != ParseFormat::Default && !input.is_empty() {
+            input.parse::<syn::Token![,]>()?;
+        }
+        let options = ParseOptions::parse_list(input)?;
+
+        if let Some(whitespace) = &options.whitespace {
+            if !matches!(format, ParseFormat::Match(_)) {
+                return Err(syn::Error::new_spanned(
+                    whitespace,
+                    "The whitespace option can only be used with a match format",
+                ));
+            }
         }
+
+        Ok(ParseAttribute { format, options })
     }
 }
 
 impl ParseFormat {
-    fn to_expr(&self) -> syn::Result<syn::Expr> {
+    /// Generate the tokens for the parser, where any error is turned into a `compile_error!`.
+    pub fn to_parser_tokens(
+        &self,
+        fields: &Fields,
+        options: &ParseOptions,
+    ) -> proc_macro2::TokenStream {
+        match self.to_expr(fields, options) {
+            Ok(expr) => expr.to_token_stream(),
+            Err(err) => err.to_compile_error(),
+        }
+    }
+
+    /// Generate the tokens for the format of an enum, which wraps the parser of the variants. All
+    /// `{}` placeholders in the format are replaced with that parser.
+    pub fn to_enum_parser_tokens(
+        &self,
+        variants: &Ident,
+        options: &ParseOptions,
+    ) -> proc_macro2::TokenStream {
+        match self.to_enum_expr(variants, options) {
+            Ok(expr) => expr.to_token_stream(),
+            Err(err) => err.to_compile_error(),
+        }
+    }
+
+    fn to_enum_expr(&self, variants: &Ident, options: &ParseOptions) -> syn::Result<syn::Expr> {
+        struct ReplacePlaceholders<'a> {
+            variants: &'a Ident,
+            found: bool,
+        }
+        impl VisitMut for ReplacePlaceholders<'_> {
+            fn visit_expr_mut(&mut self, expr: &mut syn::Expr) {
+                match expr {
+                    syn::Expr::Block(block) if block.block.stmts.is_empty() => {
+                        let variants = self.variants;
+                        *expr = parse_quote_spanned!(block.span() => #variants);
+                        self.found = true;
+                    }
+                    _ => visit_mut::visit_expr_mut(self, expr),
+                }
+            }
+        }
+
         match self {
-            ParseFormat::Match(string) => generate_match_expression(string),
             ParseFormat::Expr(expr) => {
                 let mut expr = expr.clone();
-                update_nom_expression(&mut expr)?;
+                let mut replace = ReplacePlaceholders {
+                    variants,
+                    found: false,
+                };
+                replace.visit_expr_mut(&mut expr);
+                if !replace.found {
+                    return Err(syn::Error::new_spanned(
+                        expr,
+                        "The format of an enum needs a {} placeholder for the variants",
+                    ));
+                }
+                update_nom_expression(&mut expr, options)?;
                 Ok(expr)
             }
-            ParseFormat::Default => {
-                let mut expr: syn::Expr = parse_quote!(());
-                update_nom_expression(&mut expr)?;
+            ParseFormat::Match(format) => Err(syn::Error::new_spanned(
+                &format.literal,
+                "The format of an enum can not be a match format, use an expression with a {} placeholder for the variants",
+            )),
+            ParseFormat::With(path) => Err(syn::Error::new_spanned(
+                path,
+                "The format of an enum can not be a parse_with function",
+            )),
+            ParseFormat::Default => Ok(parse_quote!(#variants)),
+        }
+    }
+
+    fn to_expr(&self, fields: &Fields, options: &ParseOptions) -> syn::Result<syn::Expr> {
+        match self {
+            ParseFormat::Match(format) => {
+                let mut options = options.clone();
+                if format.whitespace && options.whitespace.is_none() {
+                    options.whitespace = Some(parse_quote!(multispace0));
+                }
+                options.case_insensitive |= format.case_insensitive;
+                generate_match_expression(&format.literal, fields, &options)
+            }
+            ParseFormat::Expr(expr) => {
+                check_no_field_formats(fields)?;
+                let mut expr = expr.clone();
+                update_nom_expression(&mut expr, options)?;
                 Ok(expr)
             }
+            ParseFormat::With(path) => {
+                check_no_field_formats(fields)?;
+                Ok(parse_quote!(#path))
+            }
+            ParseFormat::Default => generate_fields_expression(fields, options),
+        }
+    }
+}
+
+/// Field formats are only used when the fields are parsed one by one, so they can not be combined
+/// with a single parser for the whole item.
+fn check_no_field_formats(fields: &Fields) -> syn::Result<()> {
+    for field in &fields.fields_format {
+        if let Some(format) = field.get_format() {
+            return Err(syn::Error::new_spanned(
+                format,
+                "Fields can only have a format when the whole format is a match format or is left out",
+            ));
+        }
+        if let Some(path) = field.get_parse_with() {
+            return Err(syn::Error::new_spanned(
+                path,
+                "Fields can only have a parse_with function when the whole format is a match format or is left out",
+            ));
+        }
+        if let Some(verify) = field.get_verify() {
+            return Err(syn::Error::new_spanned(
+                verify,
+                "Fields can only be verified when the whole format is a match format or is left out",
+            ));
         }
     }
+    Ok(())
 }
 
-pub fn generate_match_expression(literal: &syn::LitStr) -> syn::Result<syn::Expr> {
-    let value = literal.value();
+/// Generates a parser that parses all the fields in order, each using their own format. When the
+/// format of a field refers to an earlier field, the fields are parsed one by one in a closure,
+/// otherwise a tuple of the parsers is enough.
+fn generate_fields_expression(fields: &Fields, options: &ParseOptions) -> syn::Result<syn::Expr> {
+    let mut parsers = vec![];
+    let mut parsed_names = vec![];
+    let mut refers_to_fields = false;
+    for field in fields
+        .fields_format
+        .iter()
+        .filter(|field| field.is_parsed())
+    {
+        let mut parser = generate_field_parser(field, options)?;
+        refers_to_fields |= fields.rename_parsed_fields(&mut parser, &parsed_names)?;
+        parsers.push(parser);
+        parsed_names.push(field.get_param_name());
+    }
+
+    if refers_to_fields {
+        let statements = generate_field_statements(fields, options)?;
+        return Ok(parse_quote!(
+            (|input| {
+                #(#statements)*
+                return Ok((input, (#(#parsed_names),*)));
+            })
+        ));
+    }
+
+    match parsers.len() {
+        0 => Ok(parse_quote!(nom::combinator::success(()))),
+        1 => Ok(parsers.remove(0)),
+        _ => Ok(parse_quote!((#(#parsers),*))),
+    }
+}
+
+/// Generates a `let` statement for each parsed field, which parses the field using its own
+/// format. The format of a field can refer to the fields before it, since those are already bound.
+pub fn generate_field_statements(
+    fields: &Fields,
+    options: &ParseOptions,
+) -> syn::Result<Vec<syn::Stmt>> {
+    let mut statements = vec![];
+    let mut parsed_names = vec![];
+    for field in fields
+        .fields_format
+        .iter()
+        .filter(|field| field.is_parsed())
+    {
+        let mut parser = generate_field_parser(field, options)?;
+        fields.rename_parsed_fields(&mut parser, &parsed_names)?;
+        let name = field.get_param_name();
+        let ty = field.get_parsed_type();
+        statements.push(parse_quote! {
+            let (input, #name): (I, #ty) = #parser.parse(input)?;
+        });
+        parsed_names.push(name);
+    }
+    Ok(statements)
+}
+
+/// Generates the parser for a single field, which uses the `ParseFrom` implementation when the
+/// field has no format or parse_with function of its own.
+fn generate_field_parser(field: &FieldFormat, options: &ParseOptions) -> syn::Result<syn::Expr> {
+    if let Some(path) = field.get_parse_with() {
+        return Ok(verify_field(field, parse_quote!(#path)));
+    }
+
+    let mut expr = match field.get_format() {
+        Some(format) => format.clone(),
+        None => parse_quote!(()),
+    };
+    update_nom_expression(&mut expr, options)?;
+    Ok(verify_field(field, expr))
+}
+
+/// Wraps the parser of a field in `verify` when the field has a `#[verify(...)]` predicate. In the
+/// predicate, the name of the field is a reference to the parsed value.
+fn verify_field(field: &FieldFormat, parser: syn::Expr) -> syn::Expr {
+    let Some(predicate) = field.get_verify() else {
+        return parser;
+    };
+    let name = field.get_name();
+    let ty = field.get_parsed_type();
+    parse_quote!(nom::combinator::verify(#parser, |#name: &#ty| #predicate))
+}
+
+/// Finds the field that a placeholder refers to. Positional placeholders (`{}`) take the next
+/// parsed field, named ones (`{x}` or `{0}` for tuples) refer to the field directly.
+pub fn find_placeholder_field<'a>(
+    name: Option<&str>,
+    fields: &'a Fields,
+    next_position: &mut usize,
+    span: Span,
+) -> syn::Result<&'a FieldFormat> {
+    let Some(name) = name else {
+        let field = fields
+            .fields_format
+            .iter()
+            .filter(|field| field.is_parsed())
+            .nth(*next_position);
+        *next_position += 1;
+        return field.ok_or_else(|| {
+            syn::Error::new(
+                span,
+                "There are more placeholders than fields that can be parsed",
+            )
+        });
+    };
+
+    let field_name = if fields.is_named {
+        name.to_string()
+    } else if name.parse::<usize>().is_ok() {
+        format!("field_{}", name)
+    } else {
+        return Err(syn::Error::new(
+            span,
+            format!("Placeholder {{{}}} should be a field index", name),
+        ));
+    };
+
+    match fields
+        .fields_format
+        .iter()
+        .find(|field| *field.get_name() == field_name)
+    {
+        Some(FieldFormat::Derived { .. }) => Err(syn::Error::new(
+            span,
+            format!("Placeholder {{{}}} refers to a derived field", name),
+        )),
+        Some(FieldFormat::Default { .. }) => Err(syn::Error::new(
+            span,
+            format!("Placeholder {{{}}} refers to a default field", name),
+        )),
+        Some(field) => Ok(field),
+        None => Err(syn::Error::new(
+            span,
+            format!("Placeholder {{{}}} does not match any field", name),
+        )),
+    }
+}
+
+/// Generates the parse call for a single placeholder. Without a spec, the format or parse_with
+/// function of the field or its `ParseFrom` implementation is used. Otherwise the spec is handled
+/// just like a normal format expression. Both can refer to the fields that are matched by earlier
+/// placeholders.
+fn generate_placeholder_parser(
+    spec: Option<&str>,
+    field: &FieldFormat,
+    span: Span,
+    fields: &Fields,
+    parsed_names: &[Ident],
+    options: &ParseOptions,
+) -> syn::Result<syn::Expr> {
+    let Some(spec) = spec else {
+        if field.get_format().is_none()
+            && field.get_parse_with().is_none()
+            && field.get_verify().is_none()
+        {
+            return Ok(parse_quote!(nom_parse_trait::ParseFrom::parse(input)));
+        }
+        let mut parser = generate_field_parser(field, options)?;
+        fields.rename_parsed_fields(&mut parser, parsed_names)?;
+        return Ok(parse_quote!(#parser.parse(input)));
+    };
+
+    let mut expr = syn::parse_str::<syn::Expr>(spec).map_err(|err| {
+        syn::Error::new(
+            span,
+            format!("Invalid parser in placeholder {{:{}}}: {}", spec, err),
+        )
+    })?;
+    update_nom_expression(&mut expr, options)?;
+    let mut expr = verify_field(field, expr);
+    fields.rename_parsed_fields(&mut expr, parsed_names)?;
+    Ok(parse_quote!(#expr.parse(input)))
+}
+
+/// Generates a parser for a `match` format. When a whitespace parser is given, the literal text is
+/// split into separate tokens on whitespace, and the whitespace parser is used around all tokens.
+pub fn generate_match_expression(
+    literal: &syn::LitStr,
+    fields: &Fields,
+    options: &ParseOptions,
+) -> syn::Result<syn::Expr> {
+    let span = literal.span();
     let mut block: ExprBlock = parse_quote!({});
     let statements = &mut block.block.stmts;
-    let mut names = vec![];
-    let mut first = true;
+    let mut bound_names = vec![];
+    let mut next_position = 0;
 
-    for (index, part) in value.split("{}").enumerate() {
-        if first {
-            first = false;
-        } else {
-            let name = syn::Ident::new(&format!("field_{}", index), literal.span());
-            statements.push(parse_quote_spanned! { literal.span() =>
-                let (input, #name) = nom_parse_trait::ParseFrom::parse(input)?;
-            });
-            names.push(name);
+    let skip_whitespace: Vec<syn::Stmt> = match &options.whitespace {
+        Some(whitespace) => {
+            let mut whitespace = whitespace.clone();
+            update_nom_expression(&mut whitespace, options)?;
+            vec![parse_quote_spanned! { span =>
+                let (input, _) = #whitespace.parse(input)?;
+            }]
         }
+        None => vec![],
+    };
 
-        if !part.is_empty() {
-            let expr = generate_match_literal(part.as_bytes(), literal.span());
-            statements.push(parse_quote_spanned! { literal.span() =>
-                let (input, _) = #expr.parse(input)?;
-            });
+    for part in parse_format_string(literal)? {
+        match part {
+            FormatPart::Literal(text) => {
+                let tokens = if options.whitespace.is_some() {
+                    text.split_whitespace().collect()
+                } else {
+                    vec![text.as_str()]
+                };
+
+                for token in tokens {
+                    let expr = generate_match_literal(token.as_bytes(), span, options);
+                    statements.extend(skip_whitespace.iter().cloned());
+                    statements.push(parse_quote_spanned! { span =>
+                        let (input, _) = #expr.parse(input)?;
+                    });
+                }
+            }
+            FormatPart::Placeholder {
+                name,
+                spec,
+                span: placeholder_span,
+            } => {
+                let field = find_placeholder_field(
+                    name.as_deref(),
+                    fields,
+                    &mut next_position,
+                    placeholder_span,
+                )?;
+                let parse_call = generate_placeholder_parser(
+                    spec.as_deref(),
+                    field,
+                    placeholder_span,
+                    fields,
+                    &bound_names,
+                    options,
+                )?;
+                let name = field.get_param_name();
+                if bound_names.contains(&name) {
+                    return Err(syn::Error::new(
+                        placeholder_span,
+                        format!("Field {} is matched more than once", field.get_name()),
+                    ));
+                }
+                statements.extend(skip_whitespace.iter().cloned());
+                statements.push(parse_quote_spanned! { span =>
+                    let (input, #name) = #parse_call?;
+                });
+                bound_names.push(name);
+            }
         }
     }
+    statements.extend(skip_whitespace);
 
-    statements.push(parse_quote_spanned! { literal.span() =>
+    // The result is always in the order of the fields, regardless of the order in the format
+    let names = fields.get_expression_names();
+    if let Some(missing) = names.iter().find(|name| !bound_names.contains(name)) {
+        let field = fields
+            .fields_format
+            .iter()
+            .find(|field| field.get_param_name() == *missing)
+            .unwrap();
+        return Err(syn::Error::new(
+            span,
+            format!(
+                "Field {} is not matched by any placeholder",
+                field.get_name()
+            ),
+        ));
+    }
+
+    statements.push(parse_quote_spanned! { span =>
         return Ok((input, (#(#names),*)));
     });
 
-    Ok(parse_quote_spanned!( literal.span() => ( |input| #block )))
+    // The parens get the default span, so they don't trigger a lint when they're not needed
+    let closure: syn::Expr = parse_quote_spanned!( span => |input| #block );
+    Ok(parse_quote!((#closure)))
 }
 
-pub fn generate_match_literal(value: &[u8], span: Span) -> syn::Expr {
+pub fn generate_match_literal(value: &[u8], span: Span, options: &ParseOptions) -> syn::Expr {
     let lit = syn::LitByteStr::new(value, span);
-    parse_quote!(nom::bytes::complete::tag(#lit.as_ref()))
+    if options.case_insensitive {
+        parse_quote!(nom::bytes::complete::tag_no_case(#lit.as_ref()))
+    } else {
+        parse_quote!(nom::bytes::complete::tag(#lit.as_ref()))
+    }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::fields::parse_fields;
     use quote::ToTokens;
 
+    fn struct_fields(item: syn::ItemStruct) -> Fields {
+        let mut item = item;
+        parse_fields(&mut item.fields).unwrap()
+    }
+
     #[test]
     fn test_match_expr() {
-        let value = ParseFormat::Match(syn::LitStr::new("some {}", Span::call_site()));
-        let expr = value.to_expr().unwrap();
+        let value = ParseFormat::Match(MatchFormat {
+            literal: syn::LitStr::new("some {}", Span::call_site()),
+            whitespace: false,
+            case_insensitive: false,
+        });
+        let fields = struct_fields(parse_quote!(
+            struct Test(u32);
+        ));
+        let expr = value.to_expr(&fields, &ParseOptions::default()).unwrap();
         let expected: syn::Expr = parse_quote!(
             (|input| {
                 let (input, _) = nom::bytes::complete::tag(b"some ".as_ref()).parse(input)?;
-                let (input, field_1) = nom_parse_trait::ParseFrom::parse(input)?;
-                return Ok((input, (field_1)));
+                let (input, param_field_0) = nom_parse_trait::ParseFrom::parse(input)?;
+                return Ok((input, (param_field_0)));
             })
         );
         assert_eq!(expected, expr);
@@ -113,7 +537,10 @@ mod tests {
     #[test]
     fn test_expr_expr() {
         let value = ParseFormat::Expr(parse_quote!(preceded("test", ())));
-        let expr = value.to_expr().unwrap();
+        let fields = struct_fields(parse_quote!(
+            struct Test(u32);
+        ));
+        let expr = value.to_expr(&fields, &ParseOptions::default()).unwrap();
         let expected: syn::Expr = parse_quote!(nom::sequence::preceded(
             nom::bytes::complete::tag(b"test".as_ref()),
             nom_parse_trait::ParseFrom::parse
@@ -121,38 +548,240 @@ mod tests {
         assert_eq!(expected, expr);
     }
 
+    #[test]
+    fn test_enum_expr() {
+        let variants = Ident::new("parse_variant", Span::call_site());
+        let value = ParseFormat::Expr(parse_quote!(terminated({}, ";")));
+        let expr = value
+            .to_enum_expr(&variants, &ParseOptions::default())
+            .unwrap();
+        let expected: syn::Expr = parse_quote!(nom::sequence::terminated(
+            parse_variant,
+            nom::bytes::complete::tag(b";".as_ref())
+        ));
+        assert_eq!(expected, expr);
+
+        let value = ParseFormat::Expr(parse_quote!(terminated(alpha1, ";")));
+        let error = value
+            .to_enum_expr(&variants, &ParseOptions::default())
+            .unwrap_err();
+        assert_eq!(
+            "The format of an enum needs a {} placeholder for the variants",
+            error.to_string()
+        );
+    }
+
     #[test]
     fn test_default_expr() {
         let value = ParseFormat::Default;
-        let expr = value.to_expr().unwrap();
+        let fields = struct_fields(parse_quote!(
+            struct Test(u32);
+        ));
+        let expr = value.to_expr(&fields, &ParseOptions::default()).unwrap();
         let expected: syn::Expr = parse_quote!(nom_parse_trait::ParseFrom::parse);
         assert_eq!(expected, expr);
     }
 
+    #[test]
+    fn test_default_field_formats_expr() {
+        let value = ParseFormat::Default;
+        let fields = struct_fields(parse_quote!(
+            struct Test(#[format(hex_u32)] u32, u32, #[derived(field_0 + 1)] u32);
+        ));
+        let expr = value.to_expr(&fields, &ParseOptions::default()).unwrap();
+        let expected: syn::Expr = parse_quote!((
+            nom::number::complete::hex_u32,
+            nom_parse_trait::ParseFrom::parse
+        ));
+        assert_eq!(expected, expr);
+    }
+
+    #[test]
+    fn test_field_references_expr() {
+        let value = ParseFormat::Default;
+        let fields = struct_fields(parse_quote!(
+            struct Test {
+                len: u16,
+                #[format(count({}, len as usize))]
+                items: Vec<u32>,
+            }
+        ));
+        let expr = value.to_expr(&fields, &ParseOptions::default()).unwrap();
+        let expected: syn::Expr = parse_quote!(
+            (|input| {
+                let (input, param_len): (I, u16) =
+                    nom_parse_trait::ParseFrom::parse.parse(input)?;
+                let (input, param_items): (I, Vec<u32>) =
+                    nom::multi::count(nom_parse_trait::ParseFrom::parse, param_len as usize)
+                        .parse(input)?;
+                return Ok((input, (param_len, param_items)));
+            })
+        );
+        assert_eq!(expected, expr);
+    }
+
+    #[test]
+    fn test_converted_field_reference() {
+        let value = ParseFormat::Default;
+        let fields = struct_fields(parse_quote!(
+            struct Test {
+                #[format(be_u8)]
+                #[from(u8)]
+                len: usize,
+                #[format(count(be_u8, len))]
+                items: Vec<u8>,
+            }
+        ));
+        let error = value
+            .to_expr(&fields, &ParseOptions::default())
+            .unwrap_err();
+        assert_eq!(
+            "Field len is converted with from or map after all the fields are parsed, so a later format can not refer to it",
+            error.to_string()
+        );
+    }
+
     #[test]
     fn test_generate_parser_expr() {
-        let value = "test {}{} test";
-        let expr = generate_match_expression(&syn::LitStr::new(value, Span::call_site())).unwrap();
+        let value = syn::LitStr::new("test {}{} test", Span::call_site());
+        let fields = struct_fields(parse_quote!(
+            struct Test(u32, u32);
+        ));
+        let expr = generate_match_expression(&value, &fields, &ParseOptions::default()).unwrap();
         let expected: syn::Expr = parse_quote!(
             (|input| {
                 let (input, _) = nom::bytes::complete::tag(b"test ".as_ref()).parse(input)?;
-                let (input, field_1) = nom_parse_trait::ParseFrom::parse(input)?;
-                let (input, field_2) = nom_parse_trait::ParseFrom::parse(input)?;
+                let (input, param_field_0) = nom_parse_trait::ParseFrom::parse(input)?;
+                let (input, param_field_1) = nom_parse_trait::ParseFrom::parse(input)?;
                 let (input, _) = nom::bytes::complete::tag(b" test".as_ref()).parse(input)?;
-                return Ok((input, (field_1, field_2)));
+                return Ok((input, (param_field_0, param_field_1)));
+            })
+        );
+        assert_eq!(expected, expr);
+    }
+
+    #[test]
+    fn test_generate_named_parser_expr() {
+        let value = syn::LitStr::new("{y},{x}", Span::call_site());
+        let fields = struct_fields(parse_quote!(
+            struct Test {
+                x: u32,
+                y: u32,
+            }
+        ));
+        let expr = generate_match_expression(&value, &fields, &ParseOptions::default()).unwrap();
+        let expected: syn::Expr = parse_quote!(
+            (|input| {
+                let (input, param_y) = nom_parse_trait::ParseFrom::parse(input)?;
+                let (input, _) = nom::bytes::complete::tag(b",".as_ref()).parse(input)?;
+                let (input, param_x) = nom_parse_trait::ParseFrom::parse(input)?;
+                return Ok((input, (param_x, param_y)));
+            })
+        );
+        assert_eq!(expected, expr);
+    }
+
+    #[test]
+    fn test_generate_placeholder_spec_expr() {
+        let value = syn::LitStr::new(
+            "{:hex_u32}-{y:separated_list0(\"}\", {})}",
+            Span::call_site(),
+        );
+        let fields = struct_fields(parse_quote!(
+            struct Test {
+                x: u32,
+                y: Vec<u32>,
+            }
+        ));
+        let expr = generate_match_expression(&value, &fields, &ParseOptions::default()).unwrap();
+        #[rustfmt::skip]
+        let expected: syn::Expr = parse_quote!(
+            (|input| {
+                let (input, param_x) = nom::number::complete::hex_u32.parse(input)?;
+                let (input, _) = nom::bytes::complete::tag(b"-".as_ref()).parse(input)?;
+                let (input, param_y) = nom::multi::separated_list0(
+                    nom::bytes::complete::tag(b"}".as_ref()),
+                    nom_parse_trait::ParseFrom::parse
+                ).parse(input)?;
+                return Ok((input, (param_x, param_y)));
             })
         );
         assert_eq!(expected, expr);
     }
 
+    #[test]
+    fn test_generate_whitespace_expr() {
+        let value = syn::LitStr::new("( {} )", Span::call_site());
+        let fields = struct_fields(parse_quote!(
+            struct Test(u32);
+        ));
+        let options = ParseOptions {
+            whitespace: Some(parse_quote!(space0)),
+            ..Default::default()
+        };
+        let expr = generate_match_expression(&value, &fields, &options).unwrap();
+        let expected: syn::Expr = parse_quote!(
+            (|input| {
+                let (input, _) = nom::character::complete::space0.parse(input)?;
+                let (input, _) = nom::bytes::complete::tag(b"(".as_ref()).parse(input)?;
+                let (input, _) = nom::character::complete::space0.parse(input)?;
+                let (input, param_field_0) = nom_parse_trait::ParseFrom::parse(input)?;
+                let (input, _) = nom::character::complete::space0.parse(input)?;
+                let (input, _) = nom::bytes::complete::tag(b")".as_ref()).parse(input)?;
+                let (input, _) = nom::character::complete::space0.parse(input)?;
+                return Ok((input, (param_field_0)));
+            })
+        );
+        assert_eq!(expected, expr);
+    }
+
+    #[test]
+    fn test_generate_match_errors() {
+        let fields = struct_fields(parse_quote!(
+            struct Test {
+                x: u32,
+                #[derived(x + 1)]
+                y: u32,
+            }
+        ));
+        let error = |value: &str| {
+            let value = syn::LitStr::new(value, Span::call_site());
+            generate_match_expression(&value, &fields, &ParseOptions::default())
+                .unwrap_err()
+                .to_string()
+        };
+
+        assert_eq!("Placeholder {z} does not match any field", error("{z}"));
+        assert_eq!("Placeholder {y} refers to a derived field", error("{y}"));
+        assert_eq!("Field x is matched more than once", error("{x}{x}"));
+        assert_eq!("Field x is not matched by any placeholder", error("x"));
+        assert_eq!(
+            "There are more placeholders than fields that can be parsed",
+            error("{}{}")
+        );
+    }
+
     #[test]
     fn test_generate_match_literal() {
         let value = b"test\0\"!!";
         let span = Span::call_site();
-        let expr: syn::Expr = generate_match_literal(value, span);
+        let expr: syn::Expr = generate_match_literal(value, span, &ParseOptions::default());
         assert_eq!(
             "nom :: bytes :: complete :: tag (b\"test\\0\\\"!!\" . as_ref ())",
             &expr.to_token_stream().to_string()
         );
     }
+
+    #[test]
+    fn test_generate_case_insensitive_literal() {
+        let options = ParseOptions {
+            case_insensitive: true,
+            ..Default::default()
+        };
+        let expr: syn::Expr = generate_match_literal(b"test", Span::call_site(), &options);
+        assert_eq!(
+            "nom :: bytes :: complete :: tag_no_case (b\"test\" . as_ref ())",
+            &expr.to_token_stream().to_string()
+        );
+    }
 }
@@ -0,0 +1,163 @@
+use crate::rename_rule::RenameRule;
+use syn::parse::ParseStream;
+use syn::{Expr, Ident, LitStr, Result, Token};
+
+/// The options that can be given after the format in a `parse_from` or `format` attribute,
+/// e.g. `#[parse_from(match "{} {}", whitespace = space0)]`.
+#[derive(Clone, Default)]
+pub struct ParseOptions {
+    /// The parser used for whitespace in a whitespace-insensitive `match` format.
+    pub whitespace: Option<Expr>,
+    /// Match all string, character and byte literals case-insensitively.
+    pub case_insensitive: bool,
+    /// Also generate a `Display` implementation from the `match` format(s).
+    pub display: bool,
+    /// Also generate the `write_to` and `to_bytes` methods from a binary format.
+    pub write: bool,
+    /// The parser for the tag of an enum, which selects the variant with the matching `#[tag]`.
+    pub tag: Option<Expr>,
+    /// Select the variant of a fieldless enum by its discriminant, which is read as a number of
+    /// the type in the `#[repr(...)]` attribute.
+    pub repr: bool,
+    /// Read the discriminant as little endian (`le`) instead of big endian (`be`).
+    pub little_endian: bool,
+    /// Unit variants without a format match their name, or the name given with `#[rename]`.
+    pub keywords: bool,
+    /// Changes the names of the variants before they are matched as keywords.
+    pub rename_all: Option<RenameRule>,
+    /// When no variant of an enum matches, combine the errors of all the variants instead of
+    /// only returning the error of the last one.
+    pub all_errors: bool,
+    /// A function that checks the parsed item, where `false` turns into a `Verify` error at the
+    /// start of the item.
+    pub verify: Option<Expr>,
+    /// The names of the options as they were given, so errors can point at them.
+    pub names: Vec<Ident>,
+}
+
+const OPTION_NAMES: &[&str] = &[
+    "whitespace",
+    "case_insensitive",
+    "display",
+    "write",
+    "tag",
+    "repr",
+    "be",
+    "le",
+    "keywords",
+    "rename_all",
+    "all_errors",
+    "verify",
+];
+
+/// The options that select or name the variants of an enum.
+const ENUM_OPTIONS: &[&str] = &[
+    "tag",
+    "repr",
+    "be",
+    "le",
+    "keywords",
+    "rename_all",
+    "all_errors",
+];
+
+/// The options that apply to the whole item, so they can't be given to a single variant.
+const ITEM_OPTIONS: &[&str] = &["display", "write", "verify"];
+
+impl ParseOptions {
+    /// Checks if the input starts with an option instead of a format.
+    pub fn peek_option(input: ParseStream) -> bool {
+        let fork = input.fork();
+        match fork.parse::<Ident>() {
+            Ok(ident) if OPTION_NAMES.contains(&ident.to_string().as_str()) => {
+                fork.is_empty() || fork.peek(Token![,]) || fork.peek(Token![=])
+            }
+            _ => false,
+        }
+    }
+
+    /// Parses a comma separated list of options, until the end of the input.
+    pub fn parse_list(input: ParseStream) -> Result<Self> {
+        let mut options = ParseOptions::default();
+
+        while !input.is_empty() {
+            let name = input.parse::<Ident>()?;
+            match name.to_string().as_str() {
+                "whitespace" => {
+                    input.parse::<Token![=]>()?;
+                    options.whitespace = Some(input.parse::<Expr>()?);
+                }
+                "case_insensitive" => options.case_insensitive = true,
+                "display" => options.display = true,
+                "write" => options.write = true,
+                "repr" => options.repr = true,
+                "keywords" => options.keywords = true,
+                "all_errors" => options.all_errors = true,
+                "rename_all" => {
+                    input.parse::<Token![=]>()?;
+                    let rule = RenameRule::from_literal(&input.parse::<LitStr>()?)?;
+                    options.rename_all = Some(rule);
+                }
+                "be" => options.little_endian = false,
+                "le" => options.little_endian = true,
+                "tag" => {
+                    input.parse::<Token![=]>()?;
+                    options.tag = Some(input.parse::<Expr>()?);
+                }
+                "verify" => {
+                    input.parse::<Token![=]>()?;
+                    options.verify = Some(input.parse::<Expr>()?);
+                }
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        &name,
+                        format!("Unknown option {}", name),
+                    ))
+                }
+            }
+
+            options.names.push(name);
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(options)
+    }
+
+    /// Finds an option that is given, but can only be used on the whole item and not on a single
+    /// variant.
+    pub fn item_only_option(&self) -> Option<&Ident> {
+        self.names.iter().find(|name| {
+            let name = name.to_string();
+            ITEM_OPTIONS.contains(&name.as_str()) || ENUM_OPTIONS.contains(&name.as_str())
+        })
+    }
+
+    /// Finds an option that is given, but can only be used on an enum.
+    pub fn enum_only_option(&self) -> Option<&Ident> {
+        self.names
+            .iter()
+            .find(|name| ENUM_OPTIONS.contains(&name.to_string().as_str()))
+    }
+
+    /// Combines the options of an enum variant with the options of the enum itself, where the
+    /// options of the variant take precedence. The whitespace parser is only used by the `match`
+    /// format it is given with, so it is never inherited.
+    pub fn inherit(&self, parent: &ParseOptions) -> ParseOptions {
+        ParseOptions {
+            whitespace: self.whitespace.clone(),
+            case_insensitive: self.case_insensitive || parent.case_insensitive,
+            display: parent.display,
+            write: parent.write,
+            tag: parent.tag.clone(),
+            repr: parent.repr,
+            little_endian: parent.little_endian,
+            keywords: parent.keywords,
+            rename_all: parent.rename_all,
+            all_errors: parent.all_errors,
+            verify: parent.verify.clone(),
+            names: self.names.clone(),
+        }
+    }
+}
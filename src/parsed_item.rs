@@ -1,13 +1,15 @@
 use crate::fields::{parse_fields, Fields};
-use crate::parse_format::ParseFormat;
+use crate::parse_format::{ParseAttribute, ParseFormat};
+use crate::parse_options::ParseOptions;
 use itertools::Itertools;
 use proc_macro2::Ident;
-use quote::ToTokens;
 use syn::parse::{Parse, ParseStream};
 use syn::punctuated::Punctuated;
 use syn::spanned::Spanned;
 use syn::token::Comma;
-use syn::{Item, ItemEnum, ItemStruct, Result, Variant};
+use syn::{
+    parse_quote, Attribute, Item, ItemEnum, ItemStruct, LitInt, LitStr, Meta, Pat, Result, Variant,
+};
 
 pub enum ParsedItem {
     Struct {
@@ -24,6 +26,47 @@ pub struct ParsedVariant {
     pub name: Ident,
     pub fields: Fields,
     pub format: ParseFormat,
+    pub options: ParseOptions,
+    /// The value given with `#[tag(...)]`, for enums that select their variant by a tag.
+    pub tag: Option<Pat>,
+    /// The keyword given with `#[rename(...)]`, which replaces the name of the variant.
+    pub rename: Option<LitStr>,
+    /// The extra keywords given with `#[alias(...)]`.
+    pub aliases: Vec<LitStr>,
+    /// Variants with a higher `#[priority(...)]` are tried first, the default is 0.
+    pub priority: i32,
+    /// Variants with `#[skip]` are never produced by the parser.
+    pub skip: bool,
+    /// The variant with `#[fallback]` is produced when none of the other variants match.
+    pub fallback: Option<Fallback>,
+}
+
+/// What the `#[fallback]` variant captures from the input.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Fallback {
+    /// `#[fallback]` on a unit variant, which consumes no input.
+    Nothing,
+    /// `#[fallback(token)]`, which captures everything up to the next whitespace.
+    Token,
+    /// `#[fallback(line)]`, which captures the rest of the line.
+    Line,
+}
+
+impl Fallback {
+    fn from_attribute(attr: &Attribute) -> Result<Self> {
+        if let Meta::Path(_) = attr.meta {
+            return Ok(Fallback::Nothing);
+        }
+        let capture = attr.parse_args::<Ident>()?;
+        match capture.to_string().as_str() {
+            "token" => Ok(Fallback::Token),
+            "line" => Ok(Fallback::Line),
+            _ => Err(syn::Error::new_spanned(
+                capture,
+                "Expected #[fallback], #[fallback(token)] or #[fallback(line)]",
+            )),
+        }
+    }
 }
 
 impl Parse for ParsedItem {
@@ -46,31 +89,243 @@ impl Parse for ParsedItem {
     }
 }
 
+impl ParsedItem {
+    /// Gives the unit variants without a format their keyword as format. These are the variants
+    /// with a `#[rename]` or `#[alias]` attribute, or all of them with the `keywords` option.
+    pub fn resolve_keywords(&mut self, options: &ParseOptions) -> Result<()> {
+        let ParsedItem::Enum { variants, .. } = self else {
+            return Ok(());
+        };
+        let keywords = options.keywords || options.rename_all.is_some();
+
+        for variant in variants {
+            let renamed = variant.rename.is_some() || !variant.aliases.is_empty();
+            if !renamed
+                && (!keywords
+                    || variant.skip
+                    || variant.fallback.is_some()
+                    || variant.format != ParseFormat::Default
+                    || !variant.fields.fields_format.is_empty())
+            {
+                continue;
+            }
+
+            let error_span = variant.rename.as_ref().or(variant.aliases.first());
+            if variant.format != ParseFormat::Default {
+                return Err(syn::Error::new_spanned(
+                    error_span,
+                    "A variant with a format can not be renamed or have aliases",
+                ));
+            }
+            if !variant.fields.fields_format.is_empty() {
+                return Err(syn::Error::new_spanned(
+                    error_span,
+                    "Only unit variants can be renamed or have aliases",
+                ));
+            }
+
+            let keyword = match (&variant.rename, options.rename_all) {
+                (Some(rename), _) => rename.clone(),
+                (None, _) if !keywords => {
+                    return Err(syn::Error::new_spanned(
+                        error_span,
+                        "An alias can only be used with the keywords option or a rename",
+                    ));
+                }
+                (None, Some(rule)) => {
+                    LitStr::new(&rule.apply(&variant.name.to_string()), variant.name.span())
+                }
+                (None, None) => LitStr::new(&variant.name.to_string(), variant.name.span()),
+            };
+
+            let aliases = &variant.aliases;
+            variant.format = ParseFormat::Expr(if aliases.is_empty() {
+                parse_quote!(#keyword)
+            } else {
+                parse_quote!(alt((#keyword, #(#aliases),*)))
+            });
+        }
+
+        Ok(())
+    }
+}
+
 fn parse_variants(variants: &mut Punctuated<Variant, Comma>) -> Result<Vec<ParsedVariant>> {
     let mut result = Vec::with_capacity(variants.len());
 
     for variant in variants {
-        let format = if let Some((index, attr)) = variant
+        let ParseAttribute {
+            mut format,
+            options,
+        } = if let Some((index, attr)) = variant
             .attrs
             .iter()
             .find_position(|attr| attr.path().is_ident("format"))
         {
-            let tokens = attr.meta.require_list()?.tokens.to_token_stream().into();
+            let attribute = attr.parse_args::<ParseAttribute>()?;
+            if let Some(name) = attribute.options.item_only_option() {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    format!("The {} option can only be used on the whole item", name),
+                ));
+            }
+            variant.attrs.remove(index);
+            attribute
+        } else {
+            ParseAttribute {
+                format: ParseFormat::Default,
+                options: ParseOptions::default(),
+            }
+        };
+
+        if let Some((index, attr)) = variant
+            .attrs
+            .iter()
+            .find_position(|attr| attr.path().is_ident("parse_with"))
+        {
+            if format != ParseFormat::Default {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "A variant can not have both a format and a parse_with function",
+                ));
+            }
+            format = ParseFormat::With(attr.parse_args::<syn::Path>()?);
+            variant.attrs.remove(index);
+        }
+
+        let tag = if let Some((index, attr)) = variant
+            .attrs
+            .iter()
+            .find_position(|attr| attr.path().is_ident("tag"))
+        {
+            let pattern = attr.parse_args_with(Pat::parse_multi)?;
+            variant.attrs.remove(index);
+            Some(pattern)
+        } else {
+            None
+        };
+
+        let rename = if let Some((index, attr)) = variant
+            .attrs
+            .iter()
+            .find_position(|attr| attr.path().is_ident("rename"))
+        {
+            let rename = attr.parse_args::<LitStr>()?;
             variant.attrs.remove(index);
-            syn::parse::<ParseFormat>(tokens)?
+            Some(rename)
         } else {
-            ParseFormat::Default
+            None
+        };
+
+        let mut aliases = vec![];
+        variant.attrs.retain(|attr| {
+            if attr.path().is_ident("alias") {
+                aliases.push(attr.parse_args::<LitStr>());
+                false
+            } else {
+                true
+            }
+        });
+        let aliases = aliases.into_iter().collect::<Result<Vec<_>>>()?;
+
+        let priority = if let Some((index, attr)) = variant
+            .attrs
+            .iter()
+            .find_position(|attr| attr.path().is_ident("priority"))
+        {
+            let priority = attr.parse_args::<LitInt>()?.base10_parse::<i32>()?;
+            variant.attrs.remove(index);
+            priority
+        } else {
+            0
+        };
+
+        let skip = if let Some((index, attr)) = variant
+            .attrs
+            .iter()
+            .find_position(|attr| attr.path().is_ident("skip"))
+        {
+            attr.meta.require_path_only()?;
+            variant.attrs.remove(index);
+            true
+        } else {
+            false
         };
 
         let fields = parse_fields(&mut variant.fields)?;
+        let fallback = if let Some((index, attr)) = variant
+            .attrs
+            .iter()
+            .find_position(|attr| attr.path().is_ident("fallback"))
+        {
+            if skip {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "A variant can not be both skipped and the fallback",
+                ));
+            }
+            if format != ParseFormat::Default {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "The fallback variant can not have a format",
+                ));
+            }
+            let fallback = Fallback::from_attribute(attr)?;
+            let field_count = fields.get_expression_names().len();
+            if fallback == Fallback::Nothing && field_count != 0 {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "A fallback without a capture can only be used on a unit variant, use #[fallback(token)] or #[fallback(line)] to capture the input",
+                ));
+            }
+            if fallback != Fallback::Nothing && field_count != 1 {
+                return Err(syn::Error::new_spanned(
+                    attr,
+                    "A fallback with a capture needs a variant with a single field",
+                ));
+            }
+            variant.attrs.remove(index);
+            Some(fallback)
+        } else {
+            None
+        };
+
+        if let Some(capture) = fallback.and_then(capture_parser) {
+            format = ParseFormat::Expr(capture);
+        }
         let name = variant.ident.clone();
 
         result.push(ParsedVariant {
             name,
             fields,
             format,
+            options,
+            tag,
+            rename,
+            aliases,
+            priority,
+            skip,
+            fallback,
         });
     }
 
     Ok(result)
 }
+
+/// The parser for the text that a fallback variant captures, which is converted into its field.
+fn capture_parser(fallback: Fallback) -> Option<syn::Expr> {
+    let parser: syn::Expr = match fallback {
+        Fallback::Nothing => return None,
+        Fallback::Token => parse_quote! {
+            nom::bytes::complete::take_till1(|c: <I as nom::Input>::Item| {
+                nom::AsChar::as_char(c).is_whitespace()
+            })
+        },
+        Fallback::Line => parse_quote! { nom::character::complete::not_line_ending },
+    };
+    Some(parse_quote! {
+        nom::combinator::map(#parser, |text: I| {
+            String::from_utf8_lossy(nom::AsBytes::as_bytes(&text)).into_owned().into()
+        })
+    })
+}
@@ -1,20 +1,41 @@
-use crate::parse_format::ParseFormat;
+use crate::display_generator::generate_display;
+use crate::keyword_trie::generate_trie_dispatch;
+use crate::nom_packages::{update_nom_expression, uses_byte_parsers};
+use crate::parse_format::{generate_field_statements, ParseAttribute, ParseFormat};
+use crate::parse_options::ParseOptions;
 use crate::parsed_item::{ParsedItem, ParsedVariant};
+use crate::shadowing::warn_shadowed_variants;
+use crate::write_generator::generate_write;
 use proc_macro2::{Ident, Span, TokenStream};
 use quote::{quote, ToTokens};
-use syn::{parse_quote, GenericParam, Generics, TypeParam, WhereClause, WherePredicate};
+use syn::visit::Visit;
+use syn::{
+    parse_quote, GenericParam, Generics, ItemEnum, Type, TypeParam, WhereClause, WherePredicate,
+};
 
 pub(crate) struct ParserGenerator {
     parse_format: ParseFormat,
+    options: ParseOptions,
     parsed_item: ParsedItem,
 }
 
 impl ParserGenerator {
-    pub fn new(parse_format: ParseFormat, parsed_item: ParsedItem) -> Self {
-        Self {
-            parse_format,
-            parsed_item,
+    pub fn new(attribute: ParseAttribute, mut parsed_item: ParsedItem) -> syn::Result<Self> {
+        parsed_item.resolve_keywords(&attribute.options)?;
+        if let ParsedItem::Struct { .. } = parsed_item {
+            if let Some(name) = attribute.options.enum_only_option() {
+                return Err(syn::Error::new_spanned(
+                    name,
+                    format!("The {} option can only be used on enums", name),
+                ));
+            }
         }
+
+        Ok(Self {
+            parse_format: attribute.format,
+            options: attribute.options,
+            parsed_item,
+        })
     }
 }
 
@@ -25,91 +46,563 @@ impl ToTokens for ParserGenerator {
                 tokens.extend(object.to_token_stream());
 
                 let expression_names = fields.get_expression_names();
-                let expression = &self.parse_format;
+                let expression_types = fields.get_expression_types();
                 let derived_expressions = fields.get_derived_expressions();
                 let create_expr = fields.create_instance_expr(None);
 
-                generate_parser(
-                    tokens,
-                    &object.ident,
-                    &object.generics,
-                    quote! {
-                        let (input, (#(#expression_names),*)) = #expression.parse(input)?;
-                        #(#derived_expressions)*
-                        Ok((input, #create_expr))
-                    },
-                );
+                // Without a format, every field is parsed with its own `let`, so the format of a
+                // field can use the fields before it
+                let parse_fields = match &self.parse_format {
+                    ParseFormat::Default => {
+                        match generate_field_statements(fields, &self.options) {
+                            Ok(statements) => quote! { #(#statements)* },
+                            Err(err) => err.to_compile_error(),
+                        }
+                    }
+                    parse_format => {
+                        let expression = parse_format.to_parser_tokens(fields, &self.options);
+                        quote! {
+                            let (input, (#(#expression_names),*)): (I, (#(#expression_types),*)) =
+                                #expression.parse(input)?;
+                        }
+                    }
+                };
+
+                let content = quote! {
+                    #parse_fields
+                    #(#derived_expressions)*
+                    Ok((input, #create_expr))
+                };
+
+                let bounds = ParserBounds {
+                    parsed_types: fields.get_expression_types(),
+                    external_errors: fields.get_external_error_types(),
+                    progress_error: None,
+                    byte_input: self.options.write,
+                };
+                let content = verify_item(content, &self.options);
+                generate_parser(tokens, &object.ident, &object.generics, &bounds, content);
             }
             ParsedItem::Enum { object, variants } => {
-                if self.parse_format != ParseFormat::Default {
-                    tokens.extend(quote! { compile_error!("Enums cannot have a format") });
-                    return;
-                }
-
                 tokens.extend(object.to_token_stream());
+                if self.options.all_errors {
+                    tokens.extend(generate_progress_error(object));
+                }
 
-                let (mapping_names, mappings): (Vec<_>, Vec<_>) = variants
-                    .iter()
-                    .map(|variant| generate_variant(variant))
-                    .unzip();
+                let dispatch = generate_enum_dispatch(object, variants, &self.options)
+                    .unwrap_or_else(|err| err.to_compile_error());
 
-                generate_parser(
-                    tokens,
-                    &object.ident,
-                    &object.generics,
+                // With a format for the enum, the variants are parsed with a closure that can be
+                // used inside the format
+                let content = if self.parse_format == ParseFormat::Default {
+                    dispatch
+                } else {
+                    let parse_variant = Ident::new("parse_variant", Span::call_site());
+                    let wrapper = self
+                        .parse_format
+                        .to_enum_parser_tokens(&parse_variant, &self.options);
                     quote! {
-                        #(#mappings)*
-                        nom::branch::alt((
-                            #(#mapping_names),*
-                        )).parse(input)
-                    },
-                );
+                        #[allow(unused_mut)]
+                        let mut #parse_variant = |input: I| -> nom::IResult<I, Self, E> {
+                            #dispatch
+                        };
+                        #wrapper.parse(input)
+                    }
+                };
+
+                let parsed_variants = variants.iter().filter(|variant| !variant.skip);
+                let bounds = ParserBounds {
+                    parsed_types: parsed_variants
+                        .clone()
+                        .flat_map(|variant| variant.fields.get_expression_types())
+                        .collect(),
+                    external_errors: parsed_variants
+                        .flat_map(|variant| variant.fields.get_external_error_types())
+                        .collect(),
+                    progress_error: self.options.all_errors.then(|| progress_error_name(object)),
+                    byte_input: false,
+                };
+                let content = verify_item(content, &self.options);
+                generate_parser(tokens, &object.ident, &object.generics, &bounds, content);
             }
         }
+
+        if self.options.display {
+            tokens.extend(generate_display(&self.parse_format, &self.parsed_item));
+        }
+        if self.options.write {
+            tokens.extend(generate_write(&self.parse_format, &self.parsed_item));
+        }
     }
 }
 
-fn generate_variant(variant: &ParsedVariant) -> (Ident, TokenStream) {
-    let mapping_name = Ident::new(
-        &format!("map_{}", variant.name.to_string().to_lowercase()),
-        Span::call_site(),
+/// With the verify option, the parsed item is checked with the given function. Just like the
+/// `verify` combinator, a failed check gives a `Verify` error at the start of the item.
+fn verify_item(content: TokenStream, options: &ParseOptions) -> TokenStream {
+    match &options.verify {
+        Some(verify) => quote! {
+            nom::combinator::verify(
+                |input: I| -> nom::IResult<I, Self, E> { #content },
+                #verify,
+            )
+            .parse(input)
+        },
+        None => content,
+    }
+}
+
+/// Generates the code that selects and parses one of the variants, leaving out the variants with
+/// `#[skip]`.
+fn generate_enum_dispatch(
+    object: &ItemEnum,
+    variants: &[ParsedVariant],
+    options: &ParseOptions,
+) -> syn::Result<TokenStream> {
+    let variants = variants
+        .iter()
+        .filter(|variant| !variant.skip)
+        .collect::<Vec<_>>();
+    let mut fallbacks = variants.iter().filter(|variant| variant.fallback.is_some());
+    let fallback = fallbacks.next().copied();
+    if let Some(other) = fallbacks.next() {
+        return Err(syn::Error::new_spanned(
+            &other.name,
+            "Only one variant can have the #[fallback] attribute",
+        ));
+    }
+
+    if options.tag.is_none() {
+        if let Some(tag) = variants.iter().find_map(|variant| variant.tag.as_ref()) {
+            return Err(syn::Error::new_spanned(
+                tag,
+                "The #[tag(...)] attribute can only be used when the enum has a tag option",
+            ));
+        }
+    }
+
+    match &options.tag {
+        Some(_) if options.repr => Err(syn::Error::new(
+            Span::call_site(),
+            "The tag and repr options can not be used together",
+        )),
+        _ if options.all_errors && (options.tag.is_some() || options.repr) => Err(syn::Error::new(
+            Span::call_site(),
+            "The all_errors option can not be used with the tag or repr option",
+        )),
+        Some(tag) => generate_tag_dispatch(tag, &variants, options),
+        None if options.repr => match fallback {
+            Some(fallback) => Err(syn::Error::new_spanned(
+                &fallback.name,
+                "The #[fallback] attribute can not be used with the repr option",
+            )),
+            None => generate_repr_dispatch(object, &variants, options),
+        },
+        None => {
+            // The fallback is only tried when all the other variants fail
+            let variants = order_by_priority(&variants)
+                .into_iter()
+                .filter(|variant| variant.fallback.is_none())
+                .collect::<Vec<_>>();
+            if options.all_errors {
+                let warning = warn_shadowed_variants(&variants, options, false);
+                let variants = variants.into_iter().chain(fallback).collect::<Vec<_>>();
+                let dispatch = generate_all_errors_dispatch(object, &variants, options);
+                return Ok(quote! { #warning #dispatch });
+            }
+            let fallback = fallback.map(|fallback| generate_variant(fallback, options));
+
+            match generate_trie_dispatch(&variants, fallback.as_ref(), options) {
+                Some(dispatch) => {
+                    let warning = warn_shadowed_variants(&variants, options, true);
+                    Ok(quote! { #warning #dispatch })
+                }
+                None => {
+                    let warning = warn_shadowed_variants(&variants, options, false);
+                    let dispatch = generate_alt_dispatch(&variants, fallback, options);
+                    Ok(quote! { #warning #dispatch })
+                }
+            }
+        }
+    }
+}
+
+/// Orders the variants by their `#[priority(...)]`, where the highest priority comes first.
+/// Variants with the same priority keep their declaration order.
+fn order_by_priority<'a>(variants: &[&'a ParsedVariant]) -> Vec<&'a ParsedVariant> {
+    let mut variants = variants.to_vec();
+    variants.sort_by_key(|variant| std::cmp::Reverse(variant.priority));
+    variants
+}
+
+/// Tries all the variants in order, until one of them matches.
+fn generate_alt_dispatch(
+    variants: &[&ParsedVariant],
+    fallback: Option<TokenStream>,
+    options: &ParseOptions,
+) -> TokenStream {
+    let mapping_names = variants
+        .iter()
+        .map(|variant| {
+            Ident::new(
+                &format!("map_{}", variant.name.to_string().to_lowercase()),
+                Span::call_site(),
+            )
+        })
+        .collect::<Vec<_>>();
+    let mappings = variants
+        .iter()
+        .map(|variant| generate_variant(variant, options));
+    let alt = generate_nested_alt(
+        mapping_names
+            .iter()
+            .map(ToTokens::to_token_stream)
+            .chain(fallback)
+            .collect(),
     );
-    let format_expr = variant.format.to_token_stream();
+
+    quote! {
+        #(let #mapping_names = #mappings;)*
+        #alt.parse(input)
+    }
+}
+
+/// The largest tuple that nom implements `alt` for.
+const MAX_ALT_SIZE: usize = 21;
+
+/// Generates an `alt` for any number of parsers, by nesting `alt` calls when there are more
+/// parsers than nom supports in a single call.
+fn generate_nested_alt(mut parsers: Vec<TokenStream>) -> TokenStream {
+    while parsers.len() > MAX_ALT_SIZE {
+        parsers = parsers
+            .chunks(MAX_ALT_SIZE)
+            .map(|chunk| match chunk {
+                [parser] => parser.clone(),
+                _ => quote! { nom::branch::alt((#(#chunk),*)) },
+            })
+            .collect();
+    }
+
+    quote! { nom::branch::alt((#(#parsers),*)) }
+}
+
+/// Tries the variants one by one, like `alt`, but when none of them match, the errors of all the
+/// variants are combined with `ParseError::or`, each with the name of the variant as context.
+/// The variants are parsed with the error type of `generate_progress_error`, which records how
+/// far each variant got. The errors are combined in that order, so the deepest error is passed
+/// last.
+fn generate_all_errors_dispatch(
+    object: &ItemEnum,
+    variants: &[&ParsedVariant],
+    options: &ParseOptions,
+) -> TokenStream {
+    let progress_error = progress_error_name(object);
+    let attempts = variants.iter().map(|variant| {
+        let parser = generate_variant(variant, options);
+        let name = variant.name.to_string();
+
+        quote! {
+            let result: nom::IResult<I, Self, #progress_error<I, E>> =
+                #parser.parse(input.clone());
+            match result {
+                Ok(result) => return Ok(result),
+                Err(nom::Err::Error(progress)) => {
+                    let offset = nom::Offset::offset(&input, &progress.input);
+                    let error =
+                        nom::error::ContextError::add_context(input.clone(), #name, progress.error);
+                    errors.push((offset, error));
+                }
+                Err(nom::Err::Failure(progress)) => return Err(nom::Err::Failure(progress.error)),
+                Err(nom::Err::Incomplete(needed)) => return Err(nom::Err::Incomplete(needed)),
+            }
+        }
+    });
+
+    quote! {
+        let mut errors = Vec::new();
+        #(#attempts)*
+        errors.sort_by_key(|(offset, _)| *offset);
+        let error = errors
+            .into_iter()
+            .map(|(_, error)| error)
+            .reduce(|first, other| nom::error::ParseError::or(first, other))
+            .unwrap_or_else(|| {
+                nom::error::ParseError::from_error_kind(input, nom::error::ErrorKind::Alt)
+            });
+        Err(nom::Err::Error(error))
+    }
+}
+
+fn progress_error_name(object: &ItemEnum) -> Ident {
+    Ident::new(
+        &format!("{}ErrorProgress", object.ident),
+        object.ident.span(),
+    )
+}
+
+/// Generates the error type that an enum with the `all_errors` option parses its variants with.
+/// It wraps the error type of the parser, and keeps the input of the first error that was
+/// created, which is where the variant got stuck.
+fn generate_progress_error(object: &ItemEnum) -> TokenStream {
+    let name = progress_error_name(object);
+    let vis = &object.vis;
+
+    quote! {
+        #[doc(hidden)]
+        #vis struct #name<I, E> {
+            input: I,
+            error: E,
+        }
+
+        impl<I: Clone, E: nom::error::ParseError<I>> nom::error::ParseError<I> for #name<I, E> {
+            fn from_error_kind(input: I, kind: nom::error::ErrorKind) -> Self {
+                #name {
+                    input: input.clone(),
+                    error: E::from_error_kind(input, kind),
+                }
+            }
+
+            fn append(input: I, kind: nom::error::ErrorKind, other: Self) -> Self {
+                #name {
+                    input: other.input,
+                    error: E::append(input, kind, other.error),
+                }
+            }
+
+            fn from_char(input: I, c: char) -> Self {
+                #name {
+                    input: input.clone(),
+                    error: E::from_char(input, c),
+                }
+            }
+
+            fn or(self, other: Self) -> Self {
+                #name {
+                    input: other.input,
+                    error: self.error.or(other.error),
+                }
+            }
+        }
+
+        impl<I, E: nom::error::ContextError<I>> nom::error::ContextError<I> for #name<I, E> {
+            fn add_context(input: I, context: &'static str, other: Self) -> Self {
+                #name {
+                    input: other.input,
+                    error: E::add_context(input, context, other.error),
+                }
+            }
+        }
+
+        impl<I: Clone, E: nom::error::FromExternalError<I, X>, X> nom::error::FromExternalError<I, X>
+            for #name<I, E>
+        {
+            fn from_external_error(input: I, kind: nom::error::ErrorKind, error: X) -> Self {
+                #name {
+                    input: input.clone(),
+                    error: E::from_external_error(input, kind, error),
+                }
+            }
+        }
+    }
+}
+
+/// Parses the tag once, and then only parses the variant with the matching `#[tag(...)]`. The
+/// variant with `#[tag(_)]` or `#[fallback]` is parsed from the start of the input, including the
+/// tag, when no other variant matches.
+fn generate_tag_dispatch(
+    tag: &syn::Expr,
+    variants: &[&ParsedVariant],
+    options: &ParseOptions,
+) -> syn::Result<TokenStream> {
+    let mut tag = tag.clone();
+    update_nom_expression(&mut tag, options)?;
+
+    let mut arms = vec![];
+    let mut fallback = None;
+    for variant in variants {
+        let parser = generate_variant(variant, options);
+        let is_fallback =
+            variant.fallback.is_some() || matches!(variant.tag, Some(syn::Pat::Wild(_)));
+        match &variant.tag {
+            _ if is_fallback && fallback.is_some() => {
+                return Err(syn::Error::new_spanned(
+                    &variant.name,
+                    "Only one variant can have the #[tag(_)] or #[fallback] attribute",
+                ));
+            }
+            _ if is_fallback => fallback = Some(quote! { #parser.parse(start) }),
+            Some(pattern) => arms.push(quote! { #pattern => #parser.parse(input), }),
+            None => {
+                return Err(syn::Error::new_spanned(
+                    &variant.name,
+                    format!(
+                        "Variant {} needs a #[tag(...)] attribute, since the enum has a tag",
+                        variant.name
+                    ),
+                ));
+            }
+        }
+    }
+
+    let fallback = fallback.unwrap_or_else(|| {
+        quote! {
+            Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+                start,
+                nom::error::ErrorKind::Switch,
+            )))
+        }
+    });
+
+    Ok(quote! {
+        let start = input.clone();
+        let (input, tag) = #tag.parse(input)?;
+        match tag {
+            #(#arms)*
+            _ => #fallback,
+        }
+    })
+}
+
+/// Reads a number with the type of the `#[repr(...)]` attribute, and selects the variant with
+/// that value as its discriminant.
+fn generate_repr_dispatch(
+    object: &ItemEnum,
+    variants: &[&ParsedVariant],
+    options: &ParseOptions,
+) -> syn::Result<TokenStream> {
+    let mut repr_type = None;
+    for attr in object
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("repr"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if let Some(ident) = meta.path.get_ident() {
+                if REPR_TYPES.contains(&ident.to_string().as_str()) {
+                    repr_type = Some(ident.clone());
+                }
+            }
+            Ok(())
+        })?;
+    }
+    let Some(repr_type) = repr_type else {
+        return Err(syn::Error::new_spanned(
+            &object.ident,
+            "The repr option needs a #[repr(...)] attribute with an integer type",
+        ));
+    };
+
+    for variant in variants {
+        if !variant.fields.fields_format.is_empty()
+            || variant.format != ParseFormat::Default
+            || variant.tag.is_some()
+        {
+            return Err(syn::Error::new_spanned(
+                &variant.name,
+                format!(
+                    "Variant {} can not have fields or a format, since the enum is parsed from its repr",
+                    variant.name
+                ),
+            ));
+        }
+    }
+
+    let endian = if options.little_endian { "le" } else { "be" };
+    let mut parser = syn::parse_str(&format!("{}_{}", endian, repr_type))?;
+    update_nom_expression(&mut parser, options)?;
+    let names = variants.iter().map(|variant| &variant.name);
+
+    Ok(quote! {
+        let start = input.clone();
+        let (input, value) = #parser.parse(input)?;
+        match value {
+            #(value if value == Self::#names as #repr_type => Ok((input, Self::#names)),)*
+            _ => Err(nom::Err::Error(nom::error::ParseError::from_error_kind(
+                start,
+                nom::error::ErrorKind::Switch,
+            ))),
+        }
+    })
+}
+
+/// The integer types that can be used in `#[repr(...)]` and that nom has a number parser for.
+const REPR_TYPES: &[&str] = &[
+    "u8", "i8", "u16", "i16", "u32", "i32", "u64", "i64", "u128", "i128",
+];
+
+fn generate_variant(variant: &ParsedVariant, options: &ParseOptions) -> TokenStream {
+    let format_expr = variant
+        .format
+        .to_parser_tokens(&variant.fields, &variant.options.inherit(options));
 
     let expression_names = variant.fields.get_expression_names();
     let create_expr = variant.fields.create_instance_expr(Some(&variant.name));
 
-    let mapping = if expression_names.is_empty() {
+    if variant.fields.has_fallible_derived() {
+        // A fallible derived field returns early with an error, which is not possible in `map`
+        let expression_types = variant.fields.get_expression_types();
+        let derived_expressions = variant.fields.get_derived_expressions();
+
+        quote! {
+            (|input: I| {
+                let (input, (#(#expression_names),*)): (I, (#(#expression_types),*)) =
+                    #format_expr.parse(input)?;
+                #(#derived_expressions)*
+                Ok((input, #create_expr))
+            })
+        }
+    } else if expression_names.is_empty() {
         // Parsing a variant without fields
         quote! {
-            let #mapping_name = nom::combinator::map(#format_expr, |_| { #create_expr } );
+            nom::combinator::map(#format_expr, |_| { #create_expr } )
         }
     } else {
         let expression_types = variant.fields.get_expression_types();
         let derived_expressions = variant.fields.get_derived_expressions();
 
         quote! {
-            let #mapping_name = nom::combinator::map(
+            nom::combinator::map(
                 #format_expr,
                 |(#(#expression_names),*): (#(#expression_types),*)| {
                     #(#derived_expressions)*
                     #create_expr
                 }
-            );
+            )
         }
-    };
+    }
+}
 
-    (mapping_name, mapping)
+/// What the generated parser needs from the generic parameters, besides the bounds that every
+/// parser needs.
+struct ParserBounds {
+    /// The types of the parsed fields. Only the type parameters that are used by these types need
+    /// a `ParseFrom` implementation, so a parameter that is only used by `PhantomData` or a
+    /// default field can be any type.
+    parsed_types: Vec<Type>,
+    /// The error types of `#[try_derived(..., error = ...)]`, which the error type of the parser
+    /// is created from with `FromExternalError`.
+    external_errors: Vec<Type>,
+    /// With the `all_errors` option, the variants are parsed with the error type of
+    /// `generate_progress_error`, and the variant names are added to the errors as context.
+    progress_error: Option<Ident>,
+    /// A struct with the `write` option is always parsed from bytes, so the parser is only
+    /// implemented for byte input even if the byte parsers are only used by a nested field type.
+    byte_input: bool,
 }
 
 fn generate_parser(
     token_stream: &mut TokenStream,
     name: &Ident,
     generics: &Generics,
+    bounds: &ParserBounds,
     content: impl ToTokens,
 ) {
     let (_, type_generics, _) = generics.split_for_impl();
-    let parser_generics = parser_generics(&generics);
+    let content = content.into_token_stream();
+    let parser_generics = parser_generics(
+        generics,
+        bounds,
+        bounds.byte_input || uses_byte_parsers(&content),
+    );
     let (impl_generics, _, where_statement) = parser_generics.split_for_impl();
 
     token_stream.extend(quote! {
@@ -126,7 +619,32 @@ fn generate_parser(
     });
 }
 
-fn parser_generics(generics: &Generics) -> Generics {
+/// Checks if a generic type parameter is used by any of the types.
+fn uses_type_param(types: &[Type], param: &Ident) -> bool {
+    struct FindParam<'a> {
+        param: &'a Ident,
+        found: bool,
+    }
+    impl<'ast> Visit<'ast> for FindParam<'_> {
+        fn visit_path(&mut self, path: &'ast syn::Path) {
+            if matches!(path.segments.first(), Some(first) if first.ident == *self.param) {
+                self.found = true;
+            }
+            syn::visit::visit_path(self, path);
+        }
+    }
+
+    let mut finder = FindParam {
+        param,
+        found: false,
+    };
+    for ty in types {
+        finder.visit_type(ty);
+    }
+    finder.found
+}
+
+fn parser_generics(generics: &Generics, bounds: &ParserBounds, byte_input: bool) -> Generics {
     let mut generics = generics.clone();
 
     // If there are no generics, start a new one
@@ -140,12 +658,20 @@ fn parser_generics(generics: &Generics) -> Generics {
     let extra_parse_from_traits: Vec<WherePredicate> = generics
         .params
         .iter()
-        .flat_map(|param| {
-            if let GenericParam::Type(TypeParam { ident, .. }) = param {
-                Some(parse_quote! { #ident: nom_parse_trait::ParseFrom<I, E> })
-            } else {
-                None
+        .flat_map(|param| match param {
+            GenericParam::Type(TypeParam { ident, .. })
+                if !uses_type_param(&bounds.parsed_types, ident) =>
+            {
+                vec![]
             }
+            GenericParam::Type(TypeParam { ident, .. }) => match &bounds.progress_error {
+                Some(progress_error) => vec![
+                    parse_quote! { #ident: nom_parse_trait::ParseFrom<I, E> },
+                    parse_quote! { #ident: nom_parse_trait::ParseFrom<I, #progress_error<I, E>> },
+                ],
+                None => vec![parse_quote! { #ident: nom_parse_trait::ParseFrom<I, E> }],
+            },
+            _ => vec![],
         })
         .collect();
 
@@ -176,12 +702,23 @@ fn parser_generics(generics: &Generics) -> Generics {
     }
 
     predicates.push(parse_quote! { E: nom::error::ParseError<I> });
+    if bounds.progress_error.is_some() {
+        predicates.push(parse_quote! { E: nom::error::ContextError<I> });
+    }
+    for error in &bounds.external_errors {
+        predicates.push(parse_quote! { E: nom::error::FromExternalError<I, #error> });
+    }
     predicates.push(parse_quote! { I: nom::Input + nom::AsBytes + nom::Offset });
     predicates.push(parse_quote! { <I as nom::Input>::Item: nom::AsChar + Copy });
     predicates.push(parse_quote! { <I as nom::Input>::Iter: Clone });
+    // Used for both `tag` and `tag_no_case`, since all literals are turned into byte strings
     predicates.push(parse_quote! { I: for<'a> nom::Compare<&'a [u8]> });
     predicates.push(parse_quote! { I: nom::Compare<&'static str> });
     predicates.push(parse_quote! { for<'a> &'a str: nom::FindToken<<I as nom::Input>::Item> });
+    // The binary number parsers can only be used on byte input
+    if byte_input {
+        predicates.push(parse_quote! { I: nom::Input<Item = u8> });
+    }
 
     generics
 }
@@ -0,0 +1,85 @@
+use syn::LitStr;
+
+/// The ways that the `rename_all` option can turn the name of a variant into a keyword.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum RenameRule {
+    SnakeCase,
+    KebabCase,
+    Lowercase,
+    ScreamingSnakeCase,
+}
+
+impl RenameRule {
+    pub fn from_literal(literal: &LitStr) -> syn::Result<Self> {
+        match literal.value().as_str() {
+            "snake_case" => Ok(RenameRule::SnakeCase),
+            "kebab-case" => Ok(RenameRule::KebabCase),
+            "lowercase" => Ok(RenameRule::Lowercase),
+            "SCREAMING_SNAKE_CASE" => Ok(RenameRule::ScreamingSnakeCase),
+            value => Err(syn::Error::new_spanned(
+                literal,
+                format!(
+                    "Unknown rename rule {:?}, expected one of \"snake_case\", \"kebab-case\", \"lowercase\" or \"SCREAMING_SNAKE_CASE\"",
+                    value
+                ),
+            )),
+        }
+    }
+
+    /// Applies the rule to a name in `UpperCamelCase`.
+    pub fn apply(&self, name: &str) -> String {
+        let words = split_words(name);
+        match self {
+            RenameRule::SnakeCase => words.join("_").to_lowercase(),
+            RenameRule::KebabCase => words.join("-").to_lowercase(),
+            RenameRule::Lowercase => words.concat().to_lowercase(),
+            RenameRule::ScreamingSnakeCase => words.join("_").to_uppercase(),
+        }
+    }
+}
+
+/// Splits a name in `UpperCamelCase` into its words. A run of capitals is seen as a single word,
+/// so `HTTPGet` becomes `["HTTP", "Get"]`.
+fn split_words(name: &str) -> Vec<&str> {
+    let chars = name.char_indices().collect::<Vec<_>>();
+    let mut words = vec![];
+    let mut start = 0;
+
+    for (position, &(index, c)) in chars.iter().enumerate().skip(1) {
+        let previous = chars[position - 1].1;
+        let next_is_lower =
+            matches!(chars.get(position + 1), Some(&(_, next)) if next.is_lowercase());
+        let starts_word = c == '_'
+            || previous == '_'
+            || (c.is_uppercase() && (!previous.is_uppercase() || next_is_lower));
+
+        if starts_word {
+            words.push(&name[start..index]);
+            start = index;
+        }
+    }
+    words.push(&name[start..]);
+
+    words
+        .into_iter()
+        .map(|word| word.trim_matches('_'))
+        .filter(|word| !word.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rename_rules() {
+        assert_eq!("http_get", RenameRule::SnakeCase.apply("HttpGet"));
+        assert_eq!("http-get", RenameRule::KebabCase.apply("HTTPGet"));
+        assert_eq!("httpget", RenameRule::Lowercase.apply("HttpGet"));
+        assert_eq!(
+            "HTTP_GET_2",
+            RenameRule::ScreamingSnakeCase.apply("HttpGet_2")
+        );
+        assert_eq!("a", RenameRule::SnakeCase.apply("A"));
+    }
+}
@@ -0,0 +1,296 @@
+use crate::format_string::{parse_format_string, FormatPart};
+use crate::keyword_trie::expr_keywords;
+use crate::parse_format::ParseFormat;
+use crate::parse_options::ParseOptions;
+use crate::parsed_item::ParsedVariant;
+use proc_macro2::TokenStream;
+use quote::{quote, quote_spanned};
+use syn::{Expr, ExprCall, ExprPath, ExprTuple};
+
+/// The literal text that all the input for a variant starts with. When the variant is complete,
+/// it matches just this text, so it matches any input that starts with the text.
+struct Prefix {
+    text: Vec<u8>,
+    complete: bool,
+}
+
+/// Finds the literal prefixes of a variant, for the formats that can be analysed statically.
+fn variant_prefixes(variant: &ParsedVariant) -> Option<Vec<Prefix>> {
+    let is_unit = variant.fields.fields_format.is_empty();
+    match &variant.format {
+        ParseFormat::Default if is_unit => Some(vec![Prefix {
+            text: vec![],
+            complete: true,
+        }]),
+        ParseFormat::Match(format) if !format.whitespace => {
+            let mut text = vec![];
+            for part in parse_format_string(&format.literal).ok()? {
+                match part {
+                    FormatPart::Literal(literal) => text.extend(literal.into_bytes()),
+                    FormatPart::Placeholder { .. } => {
+                        return Some(vec![Prefix {
+                            text,
+                            complete: false,
+                        }]);
+                    }
+                }
+            }
+            Some(vec![Prefix {
+                text,
+                complete: is_unit,
+            }])
+        }
+        ParseFormat::Expr(expr) => match expr_keywords(expr) {
+            Some(keywords) => Some(
+                keywords
+                    .into_iter()
+                    .map(|text| Prefix {
+                        text,
+                        complete: is_unit,
+                    })
+                    .collect(),
+            ),
+            None => Some(vec![Prefix {
+                text: expr_prefix(expr)?,
+                complete: false,
+            }]),
+        },
+        _ => None,
+    }
+}
+
+/// Finds the literal that a sequence of parsers starts with.
+fn expr_prefix(expr: &Expr) -> Option<Vec<u8>> {
+    match expr {
+        Expr::Lit(_) => expr_keywords(expr)?.pop(),
+        Expr::Tuple(ExprTuple { elems, .. }) => expr_prefix(elems.first()?),
+        Expr::Call(ExprCall { func, args, .. }) => match func.as_ref() {
+            Expr::Path(ExprPath { path, .. })
+                if [
+                    "tag",
+                    "preceded",
+                    "terminated",
+                    "delimited",
+                    "pair",
+                    "separated_pair",
+                ]
+                .iter()
+                .any(|name| path.is_ident(name)) =>
+            {
+                expr_prefix(args.first()?)
+            }
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn fold_case(text: &[u8], case_insensitive: bool) -> Vec<u8> {
+    if case_insensitive {
+        text.to_ascii_lowercase()
+    } else {
+        text.to_vec()
+    }
+}
+
+/// Checks that every variant can be parsed, given the order in which they are tried. A variant
+/// can never be parsed when all its input starts with the text of an earlier complete variant.
+/// With `longest_match`, the variants are not tried in order, so only variants with the exact
+/// same keywords are reported.
+pub fn check_shadowed_variants(
+    variants: &[&ParsedVariant],
+    options: &ParseOptions,
+    longest_match: bool,
+) -> syn::Result<()> {
+    let analysed = variants
+        .iter()
+        .map(|variant| {
+            let case_insensitive = variant.options.inherit(options).case_insensitive
+                || matches!(&variant.format, ParseFormat::Match(format) if format.case_insensitive);
+            (variant, variant_prefixes(variant), case_insensitive)
+        })
+        .collect::<Vec<_>>();
+
+    for (index, (variant, prefixes, case_insensitive)) in analysed.iter().enumerate() {
+        let Some(prefixes) = prefixes else {
+            continue;
+        };
+
+        let shadowed_by = |prefix: &Prefix| {
+            analysed[..index]
+                .iter()
+                .find_map(|(earlier, earlier_prefixes, earlier_insensitive)| {
+                    // A case-sensitive variant can not shadow a case-insensitive one
+                    if *case_insensitive && !earlier_insensitive {
+                        return None;
+                    }
+                    let text = fold_case(&prefix.text, *earlier_insensitive);
+                    earlier_prefixes
+                        .iter()
+                        .flatten()
+                        .find(|earlier_prefix| {
+                            let earlier_text =
+                                fold_case(&earlier_prefix.text, *earlier_insensitive);
+                            earlier_prefix.complete
+                                && if longest_match {
+                                    text == earlier_text
+                                } else {
+                                    text.starts_with(&earlier_text)
+                                }
+                        })
+                        .map(|earlier_prefix| (earlier, earlier_prefix))
+                })
+        };
+
+        let shadows = prefixes.iter().map(shadowed_by).collect::<Option<Vec<_>>>();
+        if let Some((earlier, earlier_prefix)) =
+            shadows.and_then(|shadows| shadows.first().copied())
+        {
+            let message = if longest_match {
+                format!(
+                    "Variant {} can never be parsed, because variant {} has the same keyword",
+                    variant.name, earlier.name,
+                )
+            } else {
+                format!(
+                    "Variant {} can never be parsed, because variant {} matches {:?} first. Move it before {} or give it a higher #[priority(...)]",
+                    variant.name,
+                    earlier.name,
+                    String::from_utf8_lossy(&earlier_prefix.text),
+                    earlier.name,
+                )
+            };
+            return Err(syn::Error::new_spanned(&variant.name, message));
+        }
+    }
+
+    Ok(())
+}
+
+/// Reports a shadowed variant as a warning instead of an error, since the enum is still valid.
+/// Proc macros can't emit warnings on stable Rust, so the generated parser uses a deprecated
+/// constant at the name of the variant.
+pub fn warn_shadowed_variants(
+    variants: &[&ParsedVariant],
+    options: &ParseOptions,
+    longest_match: bool,
+) -> TokenStream {
+    let Err(err) = check_shadowed_variants(variants, options, longest_match) else {
+        return TokenStream::new();
+    };
+    let message = err.to_string();
+    let usage = quote_spanned! { err.span() => shadowed_variant };
+
+    quote! {
+        {
+            #[deprecated(note = #message)]
+            #[allow(non_upper_case_globals)]
+            const shadowed_variant: () = ();
+            #[allow(clippy::let_unit_value)]
+            let _ = #usage;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::parsed_item::ParsedItem;
+    use quote::quote;
+
+    fn check(item: proc_macro2::TokenStream, longest_match: bool) -> syn::Result<()> {
+        let ParsedItem::Enum { variants, .. } = syn::parse2::<ParsedItem>(item).unwrap() else {
+            panic!("Expected an enum");
+        };
+        let variants = variants.iter().collect::<Vec<_>>();
+        check_shadowed_variants(&variants, &ParseOptions::default(), longest_match)
+    }
+
+    #[test]
+    fn test_shadowed_prefix() {
+        let error = check(
+            quote! {
+                enum Test {
+                    #[format("a")]
+                    A,
+                    #[format(match "ab{}")]
+                    Ab(u32),
+                }
+            },
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            "Variant Ab can never be parsed, because variant A matches \"a\" first. Move it before A or give it a higher #[priority(...)]",
+            error.to_string()
+        );
+
+        let result = check(
+            quote! {
+                enum Test {
+                    #[format(match "ab{}")]
+                    Ab(u32),
+                    #[format("a")]
+                    A,
+                    #[format(preceded("b", {}))]
+                    B(u32),
+                }
+            },
+            false,
+        );
+        assert!(result.is_ok());
+    }
+
+    #[test]
+    fn test_shadowed_by_unit_variant() {
+        let error = check(
+            quote! {
+                enum Test {
+                    Empty,
+                    Number(u32),
+                    #[format(match "x")]
+                    X,
+                }
+            },
+            false,
+        )
+        .unwrap_err();
+        assert_eq!(
+            "Variant X can never be parsed, because variant Empty matches \"\" first. Move it before Empty or give it a higher #[priority(...)]",
+            error.to_string()
+        );
+    }
+
+    #[test]
+    fn test_duplicate_keywords() {
+        let error = check(
+            quote! {
+                enum Test {
+                    #[format("in")]
+                    In,
+                    #[format(alt(("int", "in")))]
+                    Int,
+                }
+            },
+            true,
+        );
+        assert!(error.is_ok());
+
+        let error = check(
+            quote! {
+                enum Test {
+                    #[format("in")]
+                    In,
+                    #[format(match "in")]
+                    Inside,
+                }
+            },
+            true,
+        )
+        .unwrap_err();
+        assert_eq!(
+            "Variant Inside can never be parsed, because variant In has the same keyword",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,302 @@
+use crate::fields::{FieldFormat, Fields};
+use crate::format_string::{parse_format_string, FormatPart};
+use crate::parse_format::{find_placeholder_field, ParseFormat};
+use crate::parsed_item::ParsedItem;
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Expr, ExprBlock, ExprCall, ExprLit, ExprPath, ExprTuple, Lit, LitByteStr};
+
+/// The value that a part of the format produces, and which should be written for it.
+enum Value {
+    /// The value is not stored in any field, like the prefix of `preceded`.
+    Discarded,
+    Field(Ident),
+    Tuple(Vec<Value>),
+}
+
+/// Generates the `write_to` and `to_bytes` methods, which write a struct in its binary format.
+/// Any error is turned into a `compile_error!`.
+pub(crate) fn generate_write(parse_format: &ParseFormat, parsed_item: &ParsedItem) -> TokenStream {
+    let ParsedItem::Struct { object, fields } = parsed_item else {
+        return syn::Error::new(
+            Span::call_site(),
+            "The write option can only be used on structs",
+        )
+        .to_compile_error();
+    };
+
+    let statements = match write_statements(parse_format, fields) {
+        Ok(statements) => statements,
+        Err(err) => return err.to_compile_error(),
+    };
+
+    let name = &object.ident;
+    let (impl_generics, type_generics, where_statement) = object.generics.split_for_impl();
+    let pattern = fields.create_instance_expr(None);
+
+    quote! {
+        impl #impl_generics #name #type_generics #where_statement {
+            /// Writes this value in the same binary format that it is parsed from.
+            #[allow(unused_variables)]
+            pub fn write_to(&self, out: &mut impl std::io::Write) -> std::io::Result<()> {
+                let #pattern = self;
+                #(#statements)*
+                Ok(())
+            }
+
+            /// Returns the bytes of this value in the same binary format that it is parsed from.
+            pub fn to_bytes(&self) -> Vec<u8> {
+                let mut bytes = Vec::new();
+                self.write_to(&mut bytes)
+                    .expect("writing to a Vec can not fail");
+                bytes
+            }
+        }
+    }
+}
+
+fn write_statements(parse_format: &ParseFormat, fields: &Fields) -> syn::Result<Vec<TokenStream>> {
+    for field in &fields.fields_format {
+        if let FieldFormat::Expression {
+            conversion: Some(_),
+            ty,
+            ..
+        } = field
+        {
+            return Err(syn::Error::new_spanned(
+                ty,
+                "The write option can not be used with fields that are converted with from or map",
+            ));
+        }
+    }
+
+    match parse_format {
+        ParseFormat::Expr(expr) => {
+            let mut names = fields.get_expression_names();
+            let value = match names.len() {
+                0 => Value::Discarded,
+                1 => Value::Field(names.remove(0)),
+                _ => Value::Tuple(names.into_iter().map(Value::Field).collect()),
+            };
+            write_expr(expr, &value)
+        }
+        ParseFormat::Match(format) => {
+            let mut statements = vec![];
+            let mut next_position = 0;
+            for part in parse_format_string(&format.literal)? {
+                match part {
+                    FormatPart::Literal(text) => {
+                        statements.push(write_bytes(text.as_bytes(), format.literal.span()))
+                    }
+                    FormatPart::Placeholder { name, spec, span } => {
+                        let field = find_placeholder_field(
+                            name.as_deref(),
+                            fields,
+                            &mut next_position,
+                            span,
+                        )?;
+                        match spec {
+                            Some(spec) => {
+                                let expr = syn::parse_str::<Expr>(&spec)
+                                    .map_err(|err| syn::Error::new(span, err.to_string()))?;
+                                let value = Value::Field(field.get_param_name());
+                                statements.extend(write_expr(&expr, &value)?);
+                            }
+                            None => statements.extend(write_field(field)?),
+                        }
+                    }
+                }
+            }
+            Ok(statements)
+        }
+        ParseFormat::Default => {
+            let mut statements = vec![];
+            for field in &fields.fields_format {
+                if field.is_parsed() {
+                    statements.extend(write_field(field)?);
+                }
+            }
+            Ok(statements)
+        }
+        ParseFormat::With(path) => Err(syn::Error::new_spanned(
+            path,
+            "The write option can not be used with a parse_with function",
+        )),
+    }
+}
+
+/// Writes a field using its own format, or its `write_to` method when it has no format.
+fn write_field(field: &FieldFormat) -> syn::Result<Vec<TokenStream>> {
+    if let Some(path) = field.get_parse_with() {
+        return Err(syn::Error::new_spanned(
+            path,
+            "The write option can not be used with a parse_with function",
+        ));
+    }
+
+    let value = Value::Field(field.get_param_name());
+    match field.get_format() {
+        Some(format) => write_expr(format, &value),
+        None => write_expr(&syn::parse_quote!({}), &value),
+    }
+}
+
+fn write_expr(expr: &Expr, value: &Value) -> syn::Result<Vec<TokenStream>> {
+    match expr {
+        Expr::Lit(ExprLit { lit, .. }) => Ok(vec![write_literal(lit)?]),
+        Expr::Paren(paren) => write_expr(&paren.expr, value),
+        // Both `{}` and `()` are shortcuts for the `ParseFrom` parser
+        Expr::Block(ExprBlock { block, .. }) if block.stmts.is_empty() => write_nested(expr, value),
+        Expr::Tuple(ExprTuple { elems, .. }) if elems.is_empty() => write_nested(expr, value),
+        Expr::Path(ExprPath { path, .. }) => {
+            let Some((ty, endian, range)) = path.get_ident().and_then(number_parser) else {
+                return Err(unsupported(expr));
+            };
+            let name = field_value(expr, value)?;
+            let to_bytes = Ident::new(&format!("to_{}_bytes", endian), Span::call_site());
+            Ok(vec![quote! {
+                out.write_all(&#ty::#to_bytes(*#name)[#range])?;
+            }])
+        }
+        Expr::Tuple(tuple) => {
+            let values = tuple_values(expr, value, tuple.elems.len())?;
+            write_sequence(tuple.elems.iter().zip(values))
+        }
+        Expr::Call(ExprCall { func, args, .. }) => {
+            let Expr::Path(ExprPath { path, .. }) = func.as_ref() else {
+                return Err(unsupported(expr));
+            };
+            let Some(function) = path.get_ident().map(Ident::to_string) else {
+                return Err(unsupported(expr));
+            };
+            let args = args.iter().collect::<Vec<_>>();
+
+            match (function.as_str(), args.as_slice()) {
+                ("tag", [literal]) => write_expr(literal, &Value::Discarded),
+                ("preceded", [prefix, parser]) => {
+                    write_sequence([(*prefix, &Value::Discarded), (*parser, value)])
+                }
+                ("terminated", [parser, suffix]) => {
+                    write_sequence([(*parser, value), (*suffix, &Value::Discarded)])
+                }
+                ("delimited", [prefix, parser, suffix]) => write_sequence([
+                    (*prefix, &Value::Discarded),
+                    (*parser, value),
+                    (*suffix, &Value::Discarded),
+                ]),
+                ("pair", [first, second]) => {
+                    let values = tuple_values(expr, value, 2)?;
+                    write_sequence([(*first, values[0]), (*second, values[1])])
+                }
+                ("separated_pair", [first, separator, second]) => {
+                    let values = tuple_values(expr, value, 2)?;
+                    write_sequence([
+                        (*first, values[0]),
+                        (*separator, &Value::Discarded),
+                        (*second, values[1]),
+                    ])
+                }
+                _ => Err(unsupported(expr)),
+            }
+        }
+        _ => Err(unsupported(expr)),
+    }
+}
+
+/// Fields that use their `ParseFrom` implementation are written with their own `write_to` method.
+fn write_nested(expr: &Expr, value: &Value) -> syn::Result<Vec<TokenStream>> {
+    let name = field_value(expr, value)?;
+    Ok(vec![quote! { #name.write_to(out)?; }])
+}
+
+fn write_sequence<'a>(
+    parts: impl IntoIterator<Item = (&'a Expr, &'a Value)>,
+) -> syn::Result<Vec<TokenStream>> {
+    let mut statements = vec![];
+    for (expr, value) in parts {
+        statements.extend(write_expr(expr, value)?);
+    }
+    Ok(statements)
+}
+
+fn write_literal(lit: &Lit) -> syn::Result<TokenStream> {
+    match lit {
+        Lit::Str(value) => Ok(write_bytes(value.value().as_bytes(), value.span())),
+        Lit::ByteStr(value) => Ok(write_bytes(&value.value(), value.span())),
+        Lit::Byte(value) => Ok(write_bytes(&[value.value()], value.span())),
+        Lit::Char(value) => Ok(write_bytes(
+            value.value().to_string().as_bytes(),
+            value.span(),
+        )),
+        _ => Err(syn::Error::new_spanned(
+            lit,
+            "Only supporting string, bytes or character literals for nom parsers",
+        )),
+    }
+}
+
+fn write_bytes(bytes: &[u8], span: Span) -> TokenStream {
+    let bytes = LitByteStr::new(bytes, span);
+    quote! { out.write_all(#bytes)?; }
+}
+
+/// Finds the type, endianness and the range of the bytes to write for a `be_*` or `le_*` parser.
+/// The 24 bits parsers are written from a 32 bits number, so only 3 of its bytes are used.
+fn number_parser(ident: &Ident) -> Option<(Ident, &'static str, TokenStream)> {
+    let name = ident.to_string();
+    let (endian, ty) = name.split_once('_')?;
+    let endian = match endian {
+        "be" => "be",
+        "le" => "le",
+        _ => return None,
+    };
+
+    let (ty, range) = match ty {
+        "u24" | "i24" => {
+            let range = if endian == "be" {
+                quote!(1..)
+            } else {
+                quote!(..3)
+            };
+            (ty.replace("24", "32"), range)
+        }
+        "u8" | "i8" | "u16" | "i16" | "u32" | "i32" | "u64" | "i64" | "u128" | "i128" | "f32"
+        | "f64" => (ty.to_string(), quote!(..)),
+        _ => return None,
+    };
+
+    Some((Ident::new(&ty, Span::call_site()), endian, range))
+}
+
+fn field_value<'a>(expr: &Expr, value: &'a Value) -> syn::Result<&'a Ident> {
+    match value {
+        Value::Field(name) => Ok(name),
+        Value::Discarded => Err(syn::Error::new_spanned(
+            expr,
+            "The value of this parser is not stored in a field, so it can not be written",
+        )),
+        Value::Tuple(_) => Err(syn::Error::new_spanned(
+            expr,
+            "This parser should return a tuple to match the fields",
+        )),
+    }
+}
+
+fn tuple_values<'a>(expr: &Expr, value: &'a Value, len: usize) -> syn::Result<Vec<&'a Value>> {
+    match value {
+        Value::Discarded => Ok(vec![value; len]),
+        Value::Tuple(values) if values.len() == len => Ok(values.iter().collect()),
+        _ => Err(syn::Error::new_spanned(
+            expr,
+            "The number of values returned by this parser does not match the fields",
+        )),
+    }
+}
+
+fn unsupported(expr: &Expr) -> syn::Error {
+    syn::Error::new(
+        expr.span(),
+        "The write option only supports be_* and le_* number parsers, literals, placeholders and sequences of those",
+    )
+}
@@ -0,0 +1,111 @@
+use nom::error::{ContextError, Error, ErrorKind, ParseError};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFrom;
+
+#[parse_from(all_errors)]
+#[derive(Debug, PartialEq)]
+enum Command {
+    #[format("reset")]
+    Reset,
+    #[format(match "set {}")]
+    Set(u32),
+    #[format(match "get {}")]
+    Get(u32),
+}
+
+#[parse_from(all_errors)]
+#[derive(Debug, PartialEq)]
+enum Wrapper<T> {
+    #[format(delimited("(", {}, ")"))]
+    Nested(T),
+    #[format("none")]
+    Nothing,
+}
+
+/// An error that keeps every error and context, like `VerboseError`.
+#[derive(Debug, PartialEq)]
+struct Attempts<'a> {
+    errors: Vec<(&'a str, String)>,
+}
+
+impl<'a> ParseError<&'a str> for Attempts<'a> {
+    fn from_error_kind(input: &'a str, kind: ErrorKind) -> Self {
+        Attempts {
+            errors: vec![(input, format!("{:?}", kind))],
+        }
+    }
+
+    fn append(input: &'a str, kind: ErrorKind, mut other: Self) -> Self {
+        other.errors.push((input, format!("{:?}", kind)));
+        other
+    }
+
+    fn or(mut self, other: Self) -> Self {
+        self.errors.extend(other.errors);
+        self
+    }
+}
+
+impl<'a> ContextError<&'a str> for Attempts<'a> {
+    fn add_context(input: &'a str, context: &'static str, mut other: Self) -> Self {
+        other.errors.push((input, context.to_string()));
+        other
+    }
+}
+
+#[test]
+fn test_all_errors_success() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Command::Get(2))),
+        Command::parse("get 2")
+    );
+}
+
+#[test]
+fn test_all_errors_listed() {
+    assert_eq!(
+        Err::<(&str, Command), _>(nom::Err::Error(Attempts {
+            errors: vec![
+                ("set x", "Tag".to_string()),
+                ("set x", "Reset".to_string()),
+                ("set x", "Tag".to_string()),
+                ("set x", "Get".to_string()),
+                ("x", "Digit".to_string()),
+                ("set x", "Set".to_string()),
+            ]
+        })),
+        Command::parse("set x")
+    );
+}
+
+#[test]
+fn test_deepest_error() {
+    assert_eq!(
+        Err::<(&str, Command), _>(nom::Err::Error(Error::new("x", ErrorKind::Digit))),
+        Command::parse("set x")
+    );
+    assert_eq!(
+        Err::<(&str, Wrapper<u32>), _>(nom::Err::Error(Error::new("]", ErrorKind::Tag))),
+        Wrapper::<u32>::parse("(3]")
+    );
+}
+
+#[test]
+fn test_all_errors_nested() {
+    assert_eq!(
+        Err::<(&str, Wrapper<Command>), _>(nom::Err::Error(Attempts {
+            errors: vec![
+                ("(x)", "Tag".to_string()),
+                ("(x)", "Nothing".to_string()),
+                ("x)", "Tag".to_string()),
+                ("x)", "Reset".to_string()),
+                ("x)", "Tag".to_string()),
+                ("x)", "Set".to_string()),
+                ("x)", "Tag".to_string()),
+                ("x)", "Get".to_string()),
+                ("(x)", "Nested".to_string()),
+            ]
+        })),
+        Wrapper::<Command>::parse("(x)")
+    );
+}
@@ -0,0 +1,85 @@
+use nom::error::Error;
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[parse_from((be_u16, preceded(b"\x00", le_u32)), write)]
+#[derive(Debug, PartialEq)]
+struct Header {
+    kind: u16,
+    length: u32,
+}
+
+#[parse_from(write)]
+#[derive(Debug, PartialEq)]
+struct Packet {
+    #[format(preceded(b"PK", {}))]
+    header: Header,
+    #[format(be_i24)]
+    offset: i32,
+    #[format(delimited('[', le_f32, ']'))]
+    value: f32,
+    #[derived(value > 1.0)]
+    is_large: bool,
+}
+
+#[test]
+fn test_write_header() {
+    let header = Header {
+        kind: 0x0102,
+        length: 0x03040506,
+    };
+    let bytes = header.to_bytes();
+    assert_eq!(b"\x01\x02\x00\x06\x05\x04\x03", bytes.as_slice());
+    assert_eq!(
+        Ok::<_, Error<_>>(header),
+        Header::parse_complete(bytes.as_slice())
+    );
+}
+
+#[test]
+fn test_write_nested() {
+    let packet = Packet {
+        header: Header { kind: 1, length: 2 },
+        offset: -2,
+        value: 1.5,
+        is_large: true,
+    };
+    let bytes = packet.to_bytes();
+    assert_eq!(
+        b"PK\x00\x01\x00\x02\x00\x00\x00\xff\xff\xfe[\x00\x00\xc0\x3f]",
+        bytes.as_slice()
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(packet),
+        Packet::parse_complete(bytes.as_slice())
+    );
+}
+
+#[test]
+fn test_write_match_format() {
+    #[parse_from(match "id={:be_u16};", write)]
+    struct Id(u16);
+
+    let mut out = vec![];
+    Id(0x4142).write_to(&mut out).unwrap();
+    assert_eq!(b"id=AB;", out.as_slice());
+}
+
+#[parse_from(write)]
+#[derive(Debug, PartialEq)]
+struct Outer {
+    header: Header,
+}
+
+#[test]
+fn test_write_outer() {
+    let outer = Outer {
+        header: Header { kind: 7, length: 8 },
+    };
+    let bytes = outer.to_bytes();
+    assert_eq!(b"\x00\x07\x00\x08\x00\x00\x00", bytes.as_slice());
+    assert_eq!(
+        Ok::<_, Error<_>>(outer),
+        Outer::parse_complete(bytes.as_slice())
+    );
+}
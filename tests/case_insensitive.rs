@@ -0,0 +1,68 @@
+use nom::error::Error;
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[parse_from(case_insensitive)]
+#[derive(Debug, PartialEq)]
+enum Command {
+    #[format("helo")]
+    Helo,
+    #[format(preceded("mail from:", {}))]
+    MailFrom(u32),
+    #[format(match "rcpt to:{}")]
+    RcptTo(u32),
+}
+
+#[test]
+fn test_case_insensitive_enum() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Command::Helo),
+        Command::parse_complete("HeLo")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Command::MailFrom(12)),
+        Command::parse_complete("MAIL FROM:12")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Command::RcptTo(3)),
+        Command::parse_complete("Rcpt To:3")
+    );
+}
+
+#[test]
+fn test_case_insensitive_match() {
+    #[parse_from(match nocase "select {} from {}")]
+    #[derive(Debug, PartialEq)]
+    struct Query(u32, u32);
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Query(1, 2)),
+        Query::parse_complete("SELECT 1 From 2")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Query(1, 2)),
+        Query::parse_complete(b"select 1 FROM 2".as_ref())
+    );
+}
+
+#[test]
+fn test_case_insensitive_explicit_parsers() {
+    #[parse_from(preceded((tag("set"), char('_')), {}), case_insensitive)]
+    #[derive(Debug, PartialEq)]
+    struct Set(u32);
+
+    #[parse_from(case_insensitive)]
+    #[derive(Debug, PartialEq)]
+    struct Pair {
+        #[format(terminated({}, char('x')))]
+        left: u32,
+        #[format(preceded(tag("y"), {}))]
+        right: u32,
+    }
+
+    assert_eq!(Ok::<_, Error<_>>(Set(4)), Set::parse_complete("SET_4"));
+    assert_eq!(
+        Ok::<_, Error<_>>(Pair { left: 3, right: 7 }),
+        Pair::parse_complete("3XY7")
+    );
+}
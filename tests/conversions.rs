@@ -0,0 +1,129 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::{ParseFrom, ParseFromExt};
+
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Small,
+    Large,
+}
+
+impl TryFrom<u32> for Kind {
+    type Error = ();
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Kind::Small),
+            1 => Ok(Kind::Large),
+            _ => Err(()),
+        }
+    }
+}
+
+#[derive(Debug, PartialEq)]
+struct Meters(u64);
+
+impl From<u32> for Meters {
+    fn from(value: u32) -> Self {
+        Meters(value as u64)
+    }
+}
+
+#[parse_from(match "{} {}")]
+#[derive(Debug, PartialEq)]
+struct Item {
+    #[from(u32)]
+    kind: Kind,
+    #[from(u32)]
+    length: Meters,
+}
+
+#[parse_from(separated_pair({}, ",", {}))]
+#[derive(Debug, PartialEq)]
+struct Pair {
+    #[map(double)]
+    x: u64,
+    #[from(u32)]
+    y: Meters,
+}
+
+#[parse_from(tag = be_u8)]
+#[derive(Debug, PartialEq)]
+enum Record {
+    #[tag(1)]
+    #[format(be_u32)]
+    Kind(#[from(u32)] Kind),
+    #[tag(2)]
+    #[format(be_u16)]
+    Length(#[map(u32::from)] u32),
+}
+
+#[parse_from(delimited('"', alpha0, '"'))]
+#[derive(Debug, PartialEq)]
+struct Word {
+    #[map(bytes_to_string)]
+    value: String,
+    #[derived(value.len())]
+    len: usize,
+}
+
+fn double(value: u32) -> u64 {
+    value as u64 * 2
+}
+
+fn bytes_to_string<I: nom::AsBytes>(text: I) -> String {
+    String::from_utf8_lossy(text.as_bytes()).into_owned()
+}
+
+#[test]
+fn test_from() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((
+            "",
+            Item {
+                kind: Kind::Large,
+                length: Meters(20)
+            }
+        )),
+        Item::parse("1 20")
+    );
+    assert_eq!(
+        Err::<(&str, Item), _>(nom::Err::Error(Error::new("", ErrorKind::MapRes))),
+        Item::parse("5 20")
+    );
+}
+
+#[test]
+fn test_map() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Pair { x: 8, y: Meters(3) })),
+        Pair::parse("4,3")
+    );
+}
+
+#[test]
+fn test_map_with_derived() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Word {
+            value: "abc".to_string(),
+            len: 3,
+        }),
+        Word::parse_complete("\"abc\"")
+    );
+}
+
+#[test]
+fn test_variant_conversions() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Record::Kind(Kind::Small)),
+        Record::parse_complete(b"\x01\x00\x00\x00\x00".as_slice())
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Record::Length(0x0102)),
+        Record::parse_complete(b"\x02\x01\x02".as_slice())
+    );
+    assert_eq!(
+        Err(Error::new(b"".as_slice(), ErrorKind::MapRes)),
+        Record::parse_complete(b"\x01\x00\x00\x00\x07".as_slice())
+    );
+}
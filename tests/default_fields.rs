@@ -0,0 +1,82 @@
+use nom::error::Error;
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFrom;
+use std::marker::PhantomData;
+
+#[derive(Debug, PartialEq)]
+struct Meters;
+
+#[parse_from(separated_pair({}, ",", {}))]
+#[derive(Debug, PartialEq)]
+struct Point<Unit> {
+    x: i32,
+    #[default]
+    visited: bool,
+    y: i32,
+    #[default = 10]
+    scale: u32,
+    #[derived(x * scale as i32)]
+    scaled_x: i32,
+    unit: PhantomData<Unit>,
+}
+
+#[parse_from]
+#[derive(Debug, PartialEq)]
+enum Shape {
+    #[format(match "circle {}")]
+    Circle(u32, #[default = "round"] &'static str),
+    #[format(match "square {}")]
+    Square {
+        size: u32,
+        #[default]
+        filled: bool,
+    },
+}
+
+#[parse_from(match "{}")]
+#[derive(Debug, PartialEq)]
+struct Tagged<T>(u32, PhantomData<T>);
+
+#[test]
+fn test_default_fields() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((
+            "",
+            Point::<Meters> {
+                x: 3,
+                visited: false,
+                y: -4,
+                scale: 10,
+                scaled_x: 30,
+                unit: PhantomData,
+            }
+        )),
+        Point::parse("3,-4")
+    );
+}
+
+#[test]
+fn test_default_variant_fields() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Shape::Circle(2, "round"))),
+        Shape::parse("circle 2")
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((
+            "",
+            Shape::Square {
+                size: 5,
+                filled: false
+            }
+        )),
+        Shape::parse("square 5")
+    );
+}
+
+#[test]
+fn test_phantom_data() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Tagged::<String>(7, PhantomData))),
+        Tagged::<String>::parse("7")
+    );
+}
@@ -0,0 +1,62 @@
+use nom::error::Error;
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[test]
+fn test_struct_display() {
+    #[parse_from(match "({x}, {y})", display)]
+    #[derive(Debug, PartialEq)]
+    struct Vector {
+        y: i32,
+        x: i32,
+        #[derived(x + y)]
+        sum: i32,
+    }
+
+    let vector = Vector {
+        x: 1,
+        y: -2,
+        sum: -1,
+    };
+    assert_eq!("(1, -2)", vector.to_string());
+    assert_eq!(Ok::<_, Error<_>>(vector), Vector::parse_complete("(1, -2)"));
+}
+
+#[test]
+fn test_tuple_struct_display() {
+    #[parse_from(match "{{{1}: {0}}}", display)]
+    struct Entry(u32, char);
+
+    assert_eq!("{a: 255}", Entry(255, 'a').to_string());
+}
+
+#[test]
+fn test_enum_display() {
+    #[parse_from(display)]
+    #[derive(Debug, PartialEq)]
+    enum Command {
+        #[format(match "move {} {}")]
+        Move(i32, i32),
+        #[format(match ws "say [{}]")]
+        Say(u32),
+        #[format("stop")]
+        Stop,
+    }
+
+    for (command, text) in [
+        (Command::Move(1, -1), "move 1 -1"),
+        (Command::Say(3), "say [3]"),
+        (Command::Stop, "stop"),
+    ] {
+        assert_eq!(text, command.to_string());
+        assert_eq!(Ok::<_, Error<_>>(command), Command::parse_complete(text));
+    }
+}
+
+#[test]
+fn test_generic_display() {
+    #[parse_from(match "<{}>", display)]
+    struct Wrapper<T>(T);
+
+    assert_eq!("<12>", Wrapper(12u32).to_string());
+}
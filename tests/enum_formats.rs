@@ -0,0 +1,43 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[parse_from(delimited(("[", space0), {}, (space0, "]")))]
+#[derive(Debug, PartialEq)]
+enum Value {
+    #[format(preceded("number ", {}))]
+    Number(u32),
+    #[format("none")]
+    Nothing,
+}
+
+#[test]
+fn test_enum_wrapper() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Value::Number(12)),
+        Value::parse_complete("[ number 12]")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Value::Nothing),
+        Value::parse_complete("[none  ]")
+    );
+    assert_eq!(
+        Err(Error::new("none", ErrorKind::Tag)),
+        Value::parse_complete("none")
+    );
+}
+
+#[test]
+fn test_enum_wrapper_repeated_placeholder() {
+    #[parse_from(alt(delimited("(", {}, ")"), {}))]
+    #[derive(Debug, PartialEq)]
+    enum Bit {
+        #[format("0")]
+        Zero,
+        #[format("1")]
+        One,
+    }
+
+    assert_eq!(Ok::<_, Error<_>>(Bit::One), Bit::parse_complete("(1)"));
+    assert_eq!(Ok::<_, Error<_>>(Bit::Zero), Bit::parse_complete("0"));
+}
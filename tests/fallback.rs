@@ -0,0 +1,128 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::{ParseFrom, ParseFromExt};
+
+#[parse_from(keywords, display)]
+#[derive(Debug, PartialEq)]
+enum Command {
+    Start,
+    Stop,
+    #[fallback(token)]
+    Unknown(String),
+}
+
+#[parse_from(keywords)]
+#[derive(Debug, PartialEq)]
+enum Signal {
+    Start,
+    #[skip]
+    Internal,
+    #[fallback]
+    Unknown,
+}
+
+#[parse_from]
+#[derive(Debug, PartialEq)]
+enum Line {
+    #[format(match "set {}")]
+    Set(u32),
+    #[format("reset")]
+    Reset,
+    #[fallback(line)]
+    Comment { text: String },
+}
+
+#[parse_from]
+#[derive(Debug, PartialEq)]
+enum Flag {
+    #[format("on")]
+    On,
+    #[format("off")]
+    Off,
+    #[fallback]
+    Missing,
+}
+
+#[parse_from(tag = be_u8)]
+#[derive(Debug, PartialEq)]
+enum Message {
+    #[tag(0x01)]
+    Ping,
+    #[fallback]
+    Other,
+}
+
+#[test]
+fn test_token_fallback() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Command::Stop)),
+        Command::parse("Stop")
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((" now", Command::Unknown("Restart".to_string()))),
+        Command::parse("Restart now")
+    );
+    assert_eq!(
+        Err(nom::Err::Error(Error::new("", ErrorKind::TakeTill1))),
+        Command::parse("")
+    );
+}
+
+#[test]
+fn test_skipped_variant() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("Internal", Signal::Unknown)),
+        Signal::parse("Internal")
+    );
+    assert_ne!(Signal::Start, Signal::Internal);
+}
+
+#[test]
+fn test_display_fallback() {
+    assert_eq!("Start", Command::Start.to_string());
+    assert_eq!(
+        "Restart",
+        Command::Unknown("Restart".to_string()).to_string()
+    );
+}
+
+#[test]
+fn test_line_fallback() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Line::Set(3))),
+        Line::parse("set 3")
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((
+            "\nreset",
+            Line::Comment {
+                text: "set it to 3".to_string()
+            }
+        )),
+        Line::parse("set it to 3\nreset")
+    );
+}
+
+#[test]
+fn test_unit_fallback() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Flag::On)),
+        Flag::parse("on")
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("maybe", Flag::Missing)),
+        Flag::parse("maybe")
+    );
+}
+
+#[test]
+fn test_tagged_fallback() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Message::Ping),
+        Message::parse_complete(b"\x01".as_slice())
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((b"\x07\x00".as_slice(), Message::Other)),
+        Message::parse(b"\x07\x00".as_slice())
+    );
+}
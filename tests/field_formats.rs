@@ -0,0 +1,61 @@
+use nom::error::Error;
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[test]
+fn test_named_field_formats() {
+    #[parse_from]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        #[format(terminated(hex_u32, ":"))]
+        color: u32,
+        #[format(separated_list1(",", {}))]
+        values: Vec<u32>,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test {
+            color: 0xff,
+            values: vec![1, 2],
+        }),
+        Test::parse_complete("ff:1,2")
+    );
+}
+
+#[test]
+fn test_unnamed_field_formats() {
+    #[parse_from]
+    #[derive(Debug, PartialEq)]
+    struct Test(#[format(hex_u32)] u32, #[format(preceded("=", {}))] u16);
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test(0xff, 3)),
+        Test::parse_complete("ff=3")
+    );
+}
+
+#[test]
+fn test_variant_field_formats() {
+    #[parse_from]
+    #[derive(Debug, PartialEq)]
+    enum Test {
+        #[format(preceded("#", hex_u32))]
+        Color(u32),
+        #[format(match "{} {}")]
+        Pair(u32, #[format(preceded("x", {}))] u32),
+        Size(#[format(terminated({}, "x"))] u32, u32),
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test::Color(0xff)),
+        Test::parse_complete("#ff")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Test::Pair(1, 2)),
+        Test::parse_complete("1 x2")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Test::Size(3, 4)),
+        Test::parse_complete("3x4")
+    );
+}
@@ -0,0 +1,75 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::{ParseFrom, ParseFromExt};
+
+#[parse_from]
+#[derive(Debug, PartialEq)]
+struct Packet {
+    #[format(be_u16)]
+    len: u16,
+    #[format(count(be_u8, len as usize))]
+    items: Vec<u8>,
+    #[format(be_u8)]
+    checksum: u8,
+}
+
+#[parse_from(match "{}:{}")]
+#[derive(Debug, PartialEq)]
+struct List {
+    len: u32,
+    #[format(count(preceded(",", {}), len as usize))]
+    items: Vec<u32>,
+}
+
+#[parse_from]
+#[derive(Debug, PartialEq)]
+enum Message {
+    #[format(match "s{}")]
+    Single(u32),
+    Many(
+        #[format(preceded("m", {}))] u32,
+        #[format(count(preceded(",", {}), field_0 as usize))] Vec<u32>,
+    ),
+}
+
+#[test]
+fn test_binary_length() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Packet {
+            len: 3,
+            items: vec![1, 2, 3],
+            checksum: 6,
+        }),
+        Packet::parse_complete(b"\x00\x03\x01\x02\x03\x06".as_slice())
+    );
+    assert_eq!(
+        Err(Error::new(b"".as_slice(), ErrorKind::Eof)),
+        Packet::parse_complete(b"\x00\x03\x01\x02".as_slice())
+    );
+}
+
+#[test]
+fn test_match_length() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((
+            "",
+            List {
+                len: 2,
+                items: vec![10, 20]
+            }
+        )),
+        List::parse("2:,10,20")
+    );
+}
+
+#[test]
+fn test_variant_length() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Message::Many(2, vec![4, 5]))),
+        Message::parse("m2,4,5")
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Message::Single(7))),
+        Message::parse("s7")
+    );
+}
@@ -0,0 +1,64 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::{ParseFrom, ParseFromExt};
+
+#[parse_from]
+#[derive(Debug, PartialEq)]
+enum Keyword {
+    #[format("in")]
+    In,
+    #[format("int")]
+    Int,
+    #[format(match "i")]
+    I,
+    #[format("interface")]
+    Interface,
+}
+
+#[parse_from(case_insensitive)]
+#[derive(Debug, PartialEq)]
+enum Method {
+    #[format("GET")]
+    Get,
+    #[format("POST")]
+    Post,
+    #[format("PUT")]
+    Put,
+}
+
+#[test]
+fn test_longest_match() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Keyword::Int),
+        Keyword::parse_complete("int")
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("er", Keyword::Int)),
+        Keyword::parse("inter")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Keyword::Interface),
+        Keyword::parse_complete("interface")
+    );
+    assert_eq!(Ok::<_, Error<_>>(Keyword::I), Keyword::parse_complete("i"));
+    assert_eq!(
+        Err(nom::Err::Error(Error::new("x", ErrorKind::Tag))),
+        Keyword::parse("x")
+    );
+}
+
+#[test]
+fn test_case_insensitive_keywords() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Method::Post),
+        Method::parse_complete("post")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Method::Put),
+        Method::parse_complete("pUt")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Method::Get),
+        Method::parse_complete(b"GET".as_slice())
+    );
+}
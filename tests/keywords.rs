@@ -0,0 +1,97 @@
+use nom::error::Error;
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[test]
+fn test_keywords() {
+    #[parse_from(keywords)]
+    #[derive(Debug, PartialEq)]
+    enum Command {
+        Start,
+        Stop,
+        #[format(preceded("wait ", {}))]
+        Wait(u32),
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Command::Start),
+        Command::parse_complete("Start")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Command::Wait(3)),
+        Command::parse_complete("wait 3")
+    );
+}
+
+#[test]
+fn test_rename_all() {
+    #[parse_from(keywords, rename_all = "snake_case")]
+    #[derive(Debug, PartialEq)]
+    enum Key {
+        MaxSize,
+        HTTPPort,
+        #[rename("min")]
+        MinSize,
+        #[alias("dir")]
+        #[alias("folder")]
+        Directory,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Key::MaxSize),
+        Key::parse_complete("max_size")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Key::HTTPPort),
+        Key::parse_complete("http_port")
+    );
+    assert_eq!(Ok::<_, Error<_>>(Key::MinSize), Key::parse_complete("min"));
+    assert_eq!(
+        Ok::<_, Error<_>>(Key::Directory),
+        Key::parse_complete("directory")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Key::Directory),
+        Key::parse_complete("folder")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Key::Directory),
+        Key::parse_complete("dir")
+    );
+}
+
+#[test]
+fn test_rename_rules() {
+    #[parse_from(rename_all = "kebab-case", case_insensitive, display)]
+    #[derive(Debug, PartialEq)]
+    enum Kebab {
+        ContentType,
+        ContentLength,
+    }
+
+    #[parse_from(rename_all = "SCREAMING_SNAKE_CASE")]
+    #[derive(Debug, PartialEq)]
+    enum Screaming {
+        ContentType,
+    }
+
+    #[parse_from(rename_all = "lowercase")]
+    #[derive(Debug, PartialEq)]
+    enum Lower {
+        ContentType,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Kebab::ContentLength),
+        Kebab::parse_complete("Content-Length")
+    );
+    assert_eq!("content-type", Kebab::ContentType.to_string());
+    assert_eq!(
+        Ok::<_, Error<_>>(Screaming::ContentType),
+        Screaming::parse_complete("CONTENT_TYPE")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Lower::ContentType),
+        Lower::parse_complete("contenttype")
+    );
+}
@@ -0,0 +1,118 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[test]
+fn test_more_than_21_variants() {
+    #[parse_from]
+    #[derive(Debug, PartialEq)]
+    enum Instruction {
+        #[format(preceded("v0=", {}))]
+        V0(u32),
+        #[format(preceded("v1=", {}))]
+        V1(u32),
+        #[format(preceded("v2=", {}))]
+        V2(u32),
+        #[format(preceded("v3=", {}))]
+        V3(u32),
+        #[format(preceded("v4=", {}))]
+        V4(u32),
+        #[format(preceded("v5=", {}))]
+        V5(u32),
+        #[format(preceded("v6=", {}))]
+        V6(u32),
+        #[format(preceded("v7=", {}))]
+        V7(u32),
+        #[format(preceded("v8=", {}))]
+        V8(u32),
+        #[format(preceded("v9=", {}))]
+        V9(u32),
+        #[format(preceded("v10=", {}))]
+        V10(u32),
+        #[format(preceded("v11=", {}))]
+        V11(u32),
+        #[format(preceded("v12=", {}))]
+        V12(u32),
+        #[format(preceded("v13=", {}))]
+        V13(u32),
+        #[format(preceded("v14=", {}))]
+        V14(u32),
+        #[format(preceded("v15=", {}))]
+        V15(u32),
+        #[format(preceded("v16=", {}))]
+        V16(u32),
+        #[format(preceded("v17=", {}))]
+        V17(u32),
+        #[format(preceded("v18=", {}))]
+        V18(u32),
+        #[format(preceded("v19=", {}))]
+        V19(u32),
+        #[format(preceded("v20=", {}))]
+        V20(u32),
+        #[format(preceded("v21=", {}))]
+        V21(u32),
+        #[format(preceded("v22=", {}))]
+        V22(u32),
+        #[format(preceded("v23=", {}))]
+        V23(u32),
+        #[format(preceded("v24=", {}))]
+        V24(u32),
+        #[format(preceded("v25=", {}))]
+        V25(u32),
+        #[format(preceded("v26=", {}))]
+        V26(u32),
+        #[format(preceded("v27=", {}))]
+        V27(u32),
+        #[format(preceded("v28=", {}))]
+        V28(u32),
+        #[format(preceded("v29=", {}))]
+        V29(u32),
+        #[format(preceded("v30=", {}))]
+        V30(u32),
+        #[format(preceded("v31=", {}))]
+        V31(u32),
+        #[format(preceded("v32=", {}))]
+        V32(u32),
+        #[format(preceded("v33=", {}))]
+        V33(u32),
+        #[format(preceded("v34=", {}))]
+        V34(u32),
+        #[format(preceded("v35=", {}))]
+        V35(u32),
+        #[format(preceded("v36=", {}))]
+        V36(u32),
+        #[format(preceded("v37=", {}))]
+        V37(u32),
+        #[format(preceded("v38=", {}))]
+        V38(u32),
+        #[format(preceded("v39=", {}))]
+        V39(u32),
+        #[format(preceded("v40=", {}))]
+        V40(u32),
+        #[format(preceded("v41=", {}))]
+        V41(u32),
+        #[format(preceded("v42=", {}))]
+        V42(u32),
+        #[format(preceded("v43=", {}))]
+        V43(u32),
+        #[format(preceded("v44=", {}))]
+        V44(u32),
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Instruction::V0(1)),
+        Instruction::parse_complete("v0=1")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Instruction::V21(2)),
+        Instruction::parse_complete("v21=2")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Instruction::V44(3)),
+        Instruction::parse_complete("v44=3")
+    );
+    assert_eq!(
+        Err(Error::new("v45=4", ErrorKind::Tag)),
+        Instruction::parse_complete("v45=4")
+    );
+}
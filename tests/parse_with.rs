@@ -0,0 +1,76 @@
+use nom::bytes::complete::{tag, take_while};
+use nom::error::{Error, ParseError};
+use nom::sequence::delimited;
+use nom::{AsBytes, AsChar, Compare, IResult, Input, Parser};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+fn quoted<I, E>(input: I) -> IResult<I, String, E>
+where
+    I: Input + AsBytes + Compare<&'static str>,
+    <I as Input>::Item: AsChar,
+    E: ParseError<I>,
+{
+    let (input, text) = delimited(
+        tag("\""),
+        take_while(|c: <I as Input>::Item| c.as_char() != '"'),
+        tag("\""),
+    )
+    .parse(input)?;
+    Ok((input, String::from_utf8_lossy(text.as_bytes()).to_string()))
+}
+
+#[test]
+fn test_field_parse_with() {
+    #[parse_from(match "{}={}")]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        #[parse_with(quoted)]
+        name: String,
+        #[parse_with(nom::character::complete::u32)]
+        value: u32,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test {
+            name: "a b".to_string(),
+            value: 12,
+        }),
+        Test::parse_complete("\"a b\"=12")
+    );
+}
+
+#[test]
+fn test_default_format_parse_with() {
+    #[parse_from]
+    #[derive(Debug, PartialEq)]
+    struct Test(
+        #[parse_with(quoted)] String,
+        #[format(preceded(":", {}))] u16,
+    );
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test("x".to_string(), 3)),
+        Test::parse_complete("\"x\":3")
+    );
+}
+
+#[test]
+fn test_variant_parse_with() {
+    #[parse_from]
+    #[derive(Debug, PartialEq)]
+    enum Test {
+        #[parse_with(quoted)]
+        Text(String),
+        Number(u32),
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test::Text("x y".to_string())),
+        Test::parse_complete("\"x y\"")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Test::Number(5)),
+        Test::parse_complete("5")
+    );
+}
@@ -0,0 +1,30 @@
+use nom::error::Error;
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[test]
+fn test_priority() {
+    #[parse_from]
+    #[derive(Debug, PartialEq)]
+    enum Value {
+        #[format("a")]
+        A,
+        #[priority(1)]
+        #[format(match "ab{}")]
+        Ab(u32),
+        #[format(terminated({}, ".0"))]
+        Integer(u32),
+        #[priority(2)]
+        Float(f32),
+    }
+
+    assert_eq!(Ok::<_, Error<_>>(Value::A), Value::parse_complete("a"));
+    assert_eq!(
+        Ok::<_, Error<_>>(Value::Ab(3)),
+        Value::parse_complete("ab3")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Value::Float(1.5)),
+        Value::parse_complete("1.5")
+    );
+}
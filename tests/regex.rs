@@ -0,0 +1,68 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::{ParseFrom, ParseFromExt};
+
+#[parse_from(separated_pair(
+    map(regex("[A-Z][a-z]+"), |s: I| String::from_utf8_lossy(s.as_bytes()).to_string()),
+    ": ",
+    {}
+))]
+#[derive(Debug, PartialEq)]
+struct Entry {
+    name: String,
+    value: u32,
+}
+
+#[test]
+fn test_regex() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Entry {
+            name: "Width".to_string(),
+            value: 12,
+        }),
+        Entry::parse_complete("Width: 12")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Entry {
+            name: "Width".to_string(),
+            value: 12,
+        }),
+        Entry::parse_complete(b"Width: 12".as_ref())
+    );
+}
+
+#[test]
+fn test_regex_no_match() {
+    assert_eq!(
+        Err(Error::new("width: 12", ErrorKind::RegexpMatch)),
+        Entry::parse_complete("width: 12")
+    );
+}
+
+#[parse_from(map(regex("a|ab|abc?"), |s: I| s.input_len()))]
+#[derive(Debug, PartialEq)]
+struct Longest(usize);
+
+#[test]
+fn test_regex_longest_match() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("d", Longest(3))),
+        Longest::parse("abcd")
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("x", Longest(2))),
+        Longest::parse("abx")
+    );
+}
+
+#[parse_from(map(regex("."), |s: I| s.input_len()))]
+#[derive(Debug, PartialEq)]
+struct Character(usize);
+
+#[test]
+fn test_regex_multi_byte_character() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("!", Character(2))),
+        Character::parse("é!")
+    );
+}
@@ -0,0 +1,48 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[parse_from(repr)]
+#[repr(u8)]
+#[derive(Debug, PartialEq)]
+enum Opcode {
+    Ping = 1,
+    Pong,
+    Close = 0x10,
+}
+
+#[parse_from(repr, le)]
+#[repr(u16)]
+#[derive(Debug, PartialEq)]
+enum Kind {
+    Small = 0x0001,
+    Large = 0x0100,
+}
+
+#[test]
+fn test_repr_u8() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Opcode::Pong),
+        Opcode::parse_complete(b"\x02".as_slice())
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Opcode::Close),
+        Opcode::parse_complete(b"\x10".as_slice())
+    );
+    assert_eq!(
+        Err(Error::new(b"\x03".as_slice(), ErrorKind::Switch)),
+        Opcode::parse_complete(b"\x03".as_slice())
+    );
+}
+
+#[test]
+fn test_repr_little_endian() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Kind::Small),
+        Kind::parse_complete(b"\x01\x00".as_slice())
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Kind::Large),
+        Kind::parse_complete(b"\x00\x01".as_slice())
+    );
+}
@@ -27,3 +27,104 @@ fn test_vector() {
         Test::parse_complete("(1,2)")
     );
 }
+
+#[test]
+fn test_named_placeholders() {
+    #[parse_from(match "{y},{x}")]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        x: u32,
+        y: u32,
+        #[derived(x + y)]
+        sum: u32,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test { x: 1, y: 3, sum: 4 }),
+        Test::parse_complete("3,1")
+    );
+}
+
+#[test]
+fn test_indexed_placeholders() {
+    #[parse_from(match "{1}: {0}")]
+    #[derive(Debug, PartialEq)]
+    struct Test(u32, i64);
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test(1, -3)),
+        Test::parse_complete("-3: 1")
+    );
+}
+
+#[test]
+fn test_placeholder_parsers() {
+    #[parse_from(match "#{:hex_u32} [{values:separated_list0(\",\", {})}] {count:u8}")]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        color: u32,
+        count: u8,
+        values: Vec<u32>,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test {
+            color: 0xff00ff,
+            count: 12,
+            values: vec![1, 2, 3],
+        }),
+        Test::parse_complete("#ff00ff [1,2,3] 12")
+    );
+}
+
+#[test]
+fn test_whitespace_insensitive() {
+    #[parse_from(match ws "( {}, {} )")]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        x: u32,
+        y: u32,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test { x: 1, y: 3 }),
+        Test::parse_complete("(1,3)")
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Test { x: 1, y: 3 }),
+        Test::parse_complete("  (\n1 ,   3 )  ")
+    );
+}
+
+#[test]
+fn test_custom_whitespace() {
+    #[parse_from(match "{} = {}", whitespace = space0)]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        key: u32,
+        value: u32,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test { key: 1, value: 3 }),
+        Test::parse_complete("1\t=3")
+    );
+    assert_eq!(
+        Err(Error::from_error_kind("\n=3", ErrorKind::Tag)),
+        Test::parse_complete("1\n=3")
+    );
+}
+
+#[test]
+fn test_escaped_braces() {
+    #[parse_from(match "{{\"a\": {}}}")]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        a: u32,
+    }
+
+    assert_eq!(
+        Ok::<_, Error<_>>(Test { a: 12 }),
+        Test::parse_complete("{\"a\": 12}")
+    );
+}
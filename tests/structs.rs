@@ -47,6 +47,30 @@ fn test_derived_named_field() {
     assert_eq!(Ok::<_, Error<_>>(expected), Test::parse_complete(input));
 }
 
+#[test]
+fn test_derived_from_derived_field() {
+    #[parse_from(separated_pair({}, ",", {}))]
+    #[derive(Debug, PartialEq)]
+    struct Test {
+        #[derived(total / 2)]
+        half: u32,
+        a: u32,
+        b: u32,
+        #[derived(a + b)]
+        total: u32,
+    }
+
+    let input = "3,5";
+    let expected = Test {
+        half: 4,
+        a: 3,
+        b: 5,
+        total: 8,
+    };
+
+    assert_eq!(Ok::<_, Error<_>>(expected), Test::parse_complete(input));
+}
+
 #[test]
 fn test_singe_unnamed_field() {
     #[parse_from(())]
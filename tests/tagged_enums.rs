@@ -0,0 +1,62 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFromExt;
+
+#[parse_from(tag = be_u8)]
+#[derive(Debug, PartialEq)]
+enum Message {
+    #[tag(0x01)]
+    Ping,
+    #[tag(0x02)]
+    #[format(be_u16)]
+    Data(u16),
+    #[tag(0x03 | 0x04)]
+    #[format((be_u8, be_u8))]
+    Pair(u8, u8),
+}
+
+#[parse_from(tag = be_u8)]
+#[derive(Debug, PartialEq)]
+enum Command {
+    #[tag(b'q')]
+    Quit,
+    #[tag(_)]
+    #[format(be_u8)]
+    Unknown(u8),
+}
+
+#[test]
+fn test_tag_dispatch() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Message::Ping),
+        Message::parse_complete(b"\x01".as_slice())
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Message::Data(0x0102)),
+        Message::parse_complete(b"\x02\x01\x02".as_slice())
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Message::Pair(5, 6)),
+        Message::parse_complete(b"\x04\x05\x06".as_slice())
+    );
+}
+
+#[test]
+fn test_unknown_tag() {
+    assert_eq!(
+        Err(Error::new(b"\x07\x00".as_slice(), ErrorKind::Switch)),
+        Message::parse_complete(b"\x07\x00".as_slice())
+    );
+}
+
+#[test]
+fn test_catch_all_tag() {
+    assert_eq!(
+        Ok::<_, Error<_>>(Command::Quit),
+        Command::parse_complete(b"q".as_slice())
+    );
+    assert_eq!(
+        Ok::<_, Error<_>>(Command::Unknown(b'x')),
+        Command::parse_complete(b"x".as_slice())
+    );
+}
@@ -0,0 +1,105 @@
+use nom::error::{Error, ErrorKind, FromExternalError, ParseError};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFrom;
+use std::num::TryFromIntError;
+
+#[parse_from(match "{}")]
+#[derive(Debug, PartialEq)]
+struct Small {
+    value: u32,
+    #[try_derived(u8::try_from(value))]
+    byte: u8,
+}
+
+#[parse_from(match "{}/{}")]
+#[derive(Debug, PartialEq)]
+struct Ratio {
+    numerator: u32,
+    denominator: u32,
+    #[try_derived(u8::try_from(numerator / denominator), error = TryFromIntError)]
+    quotient: u8,
+}
+
+#[parse_from]
+#[derive(Debug, PartialEq)]
+enum Value {
+    #[format(match "b{}")]
+    Byte(u32, #[try_derived(u8::try_from(field_0))] u8),
+    #[format(match "w{}")]
+    Word(u32),
+}
+
+/// An error that keeps the message of the external error.
+#[derive(Debug, PartialEq)]
+struct Message(String);
+
+impl<I> ParseError<I> for Message {
+    fn from_error_kind(_input: I, kind: ErrorKind) -> Self {
+        Message(format!("{:?}", kind))
+    }
+
+    fn append(_input: I, _kind: ErrorKind, other: Self) -> Self {
+        other
+    }
+}
+
+impl<I> FromExternalError<I, TryFromIntError> for Message {
+    fn from_external_error(_input: I, _kind: ErrorKind, error: TryFromIntError) -> Self {
+        Message(error.to_string())
+    }
+}
+
+#[test]
+fn test_try_derived() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((
+            "",
+            Small {
+                value: 42,
+                byte: 42
+            }
+        )),
+        Small::parse("42")
+    );
+    assert_eq!(
+        Err::<(&str, Small), _>(nom::Err::Error(Error::new("", ErrorKind::MapRes))),
+        Small::parse("300")
+    );
+}
+
+#[test]
+fn test_external_error() {
+    assert_eq!(
+        Ok::<_, nom::Err<Message>>((
+            "",
+            Ratio {
+                numerator: 10,
+                denominator: 5,
+                quotient: 2
+            }
+        )),
+        Ratio::parse("10/5")
+    );
+    assert_eq!(
+        Err::<(&str, Ratio), _>(nom::Err::Error(Message(
+            "out of range integral type conversion attempted".to_string()
+        ))),
+        Ratio::parse("1000/2")
+    );
+}
+
+#[test]
+fn test_try_derived_variant() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Value::Byte(7, 7))),
+        Value::parse("b7")
+    );
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Value::Word(700))),
+        Value::parse("w700")
+    );
+    assert_eq!(
+        Err::<(&str, Value), _>(nom::Err::Error(Error::new("b700", ErrorKind::Tag))),
+        Value::parse("b700")
+    );
+}
@@ -0,0 +1,99 @@
+use nom::error::{Error, ErrorKind};
+use nom_parse_macros::parse_from;
+use nom_parse_trait::ParseFrom;
+
+#[parse_from(match "{}-{}")]
+#[derive(Debug, PartialEq)]
+struct Range {
+    #[verify(*start <= 255)]
+    start: u32,
+    #[verify(*end >= start)]
+    end: u32,
+}
+
+#[parse_from]
+#[derive(Debug, PartialEq)]
+struct List {
+    #[format(separated_list1(",", {}))]
+    #[verify(items.len() <= 3)]
+    items: Vec<u32>,
+}
+
+#[parse_from(separated_pair({}, ",", {}), verify = Self::is_inside)]
+#[derive(Debug, PartialEq)]
+struct Point {
+    x: i32,
+    y: i32,
+}
+
+impl Point {
+    fn is_inside(&self) -> bool {
+        self.x.abs() <= 10 && self.y.abs() <= 10
+    }
+}
+
+#[parse_from(verify = |command: &Command| *command != Command::Stop)]
+#[derive(Debug, PartialEq)]
+enum Command {
+    #[format("go")]
+    Go,
+    #[format("stop")]
+    Stop,
+}
+
+#[test]
+fn test_verify_field() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Range { start: 3, end: 7 })),
+        Range::parse("3-7")
+    );
+    assert_eq!(
+        Err::<(&str, Range), _>(nom::Err::Error(Error::new("300-400", ErrorKind::Verify))),
+        Range::parse("300-400")
+    );
+    assert_eq!(
+        Err::<(&str, Range), _>(nom::Err::Error(Error::new("2", ErrorKind::Verify))),
+        Range::parse("7-2")
+    );
+}
+
+#[test]
+fn test_verify_field_format() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>((
+            "",
+            List {
+                items: vec![1, 2, 3]
+            }
+        )),
+        List::parse("1,2,3")
+    );
+    assert_eq!(
+        Err::<(&str, List), _>(nom::Err::Error(Error::new("1,2,3,4", ErrorKind::Verify))),
+        List::parse("1,2,3,4")
+    );
+}
+
+#[test]
+fn test_verify_item() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Point { x: 3, y: -4 })),
+        Point::parse("3,-4")
+    );
+    assert_eq!(
+        Err::<(&str, Point), _>(nom::Err::Error(Error::new("3,40", ErrorKind::Verify))),
+        Point::parse("3,40")
+    );
+}
+
+#[test]
+fn test_verify_enum() {
+    assert_eq!(
+        Ok::<_, nom::Err<Error<_>>>(("", Command::Go)),
+        Command::parse("go")
+    );
+    assert_eq!(
+        Err::<(&str, Command), _>(nom::Err::Error(Error::new("stop", ErrorKind::Verify))),
+        Command::parse("stop")
+    );
+}